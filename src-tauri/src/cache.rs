@@ -0,0 +1,587 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+use sha1::{Digest, Sha1};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::{LauncherError, Result};
+use crate::versions;
+
+/// How many files to walk between `cache://progress` events.
+const PROGRESS_INTERVAL: u64 = 1000;
+
+/// Library directories written by the Forge/NeoForge installer processors.
+/// No version JSON lists them, so they must never be treated as orphans.
+const PROTECTED_LIBRARY_DIRS: &[&str] = &[
+  "net/minecraft/client",
+  "net/minecraft/server",
+  "net/minecraftforge/forge",
+  "net/neoforged/neoforge",
+  "net/neoforged/forge",
+];
+
+/// Everything the installed versions need from the shared stores.
+#[derive(Debug, Default)]
+pub struct References {
+  /// Relative to `libraries/`, with the expected SHA1 when the JSON has one.
+  pub libraries: HashMap<PathBuf, Option<String>>,
+  pub asset_indexes: HashSet<String>,
+  pub objects: HashSet<String>,
+  /// Set when a referenced asset index is missing or unreadable, in which
+  /// case the referenced object set is incomplete.
+  pub objects_incomplete: bool,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcReport {
+  pub removed_files: u64,
+  pub freed_bytes: u64,
+  /// Orphans that could not be deleted, e.g. locked by another process.
+  pub failed_files: u64,
+}
+
+pub fn libraries_dir(minecraft_dir: &Path) -> PathBuf {
+  minecraft_dir.join("libraries")
+}
+
+pub fn indexes_dir(minecraft_dir: &Path) -> PathBuf {
+  minecraft_dir.join("assets").join("indexes")
+}
+
+pub fn objects_dir(minecraft_dir: &Path) -> PathBuf {
+  minecraft_dir.join("assets").join("objects")
+}
+
+pub fn object_path(minecraft_dir: &Path, hash: &str) -> PathBuf {
+  objects_dir(minecraft_dir)
+    .join(hash.get(..2).unwrap_or(hash))
+    .join(hash)
+}
+
+/// Calls `f` for every regular file below `dir`. Symlinks are not followed.
+pub(crate) fn walk_files(dir: &Path, f: &mut dyn FnMut(&Path, &fs::Metadata)) -> io::Result<()> {
+  if !dir.is_dir() {
+    return Ok(());
+  }
+  for entry in fs::read_dir(dir)? {
+    let entry = entry?;
+    let file_type = entry.file_type()?;
+    let path = entry.path();
+    if file_type.is_dir() {
+      walk_files(&path, f)?;
+    } else if file_type.is_file() {
+      f(&path, &entry.metadata()?);
+    }
+  }
+  Ok(())
+}
+
+pub fn sha1_file(path: &Path) -> Result<String> {
+  let mut file = fs::File::open(path)?;
+  let mut hasher = Sha1::new();
+  let mut buf = vec![0; 64 * 1024];
+  loop {
+    let n = file.read(&mut buf)?;
+    if n == 0 {
+      break;
+    }
+    hasher.update(&buf[..n]);
+  }
+  Ok(
+    hasher
+      .finalize()
+      .iter()
+      .map(|b| format!("{b:02x}"))
+      .collect(),
+  )
+}
+
+/// Object hashes listed in `assets/indexes/<id>.json`.
+pub fn read_index_objects(
+  minecraft_dir: &Path,
+  index_id: &str,
+) -> Result<Vec<(String, Option<u64>)>> {
+  let path = indexes_dir(minecraft_dir).join(format!("{index_id}.json"));
+  let text = fs::read_to_string(&path)?;
+  let json: Value =
+    serde_json::from_str(&text).map_err(|source| LauncherError::MalformedJson { path, source })?;
+  let Some(objects) = json["objects"].as_object() else {
+    return Ok(Vec::new());
+  };
+  Ok(
+    objects
+      .values()
+      .filter_map(|o| Some((o["hash"].as_str()?.to_string(), o["size"].as_u64())))
+      .collect(),
+  )
+}
+
+/// Collects references from every installed version except `skip`.
+///
+/// Fails if any version JSON is unreadable: without it we cannot know what
+/// that version needs, and garbage collection would be unsafe. Also fails if
+/// there is no `versions/` folder at all, which means `minecraft_dir` is the
+/// wrong path rather than that nothing is installed.
+pub fn collect_references(minecraft_dir: &Path, skip: Option<&str>) -> Result<References> {
+  let versions_dir = versions::versions_dir(minecraft_dir);
+  if !versions_dir.is_dir() {
+    return Err(LauncherError::NotADirectory(versions_dir));
+  }
+  let ids: Vec<String> = versions::installed_ids(minecraft_dir)?
+    .into_iter()
+    .filter(|id| skip != Some(id.as_str()))
+    .collect();
+  collect_references_for(minecraft_dir, &ids)
+}
+
+pub fn collect_references_for(minecraft_dir: &Path, ids: &[String]) -> Result<References> {
+  let mut refs = References::default();
+  for id in ids {
+    let json = versions::read_version_json(minecraft_dir, id)?;
+    for file in versions::library_files(&json) {
+      let sha1 = refs.libraries.entry(file.path).or_default();
+      if sha1.is_none() {
+        *sha1 = file.sha1;
+      }
+    }
+    if let Some(index) = versions::asset_index_id(&json) {
+      refs.asset_indexes.insert(index.to_string());
+    }
+  }
+
+  for index in &refs.asset_indexes {
+    match read_index_objects(minecraft_dir, index) {
+      Ok(objects) => refs
+        .objects
+        .extend(objects.into_iter().map(|(hash, _)| hash)),
+      Err(e) => {
+        log::warn!("asset index {index} unavailable: {e}");
+        refs.objects_incomplete = true;
+      }
+    }
+  }
+  Ok(refs)
+}
+
+pub(crate) fn is_protected_library(relative: &Path) -> bool {
+  PROTECTED_LIBRARY_DIRS
+    .iter()
+    .any(|dir| relative.starts_with(dir))
+}
+
+/// Files in the shared stores that no installed version references.
+pub fn find_orphans(minecraft_dir: &Path, refs: &References) -> Result<Vec<(PathBuf, u64)>> {
+  let mut orphans = Vec::new();
+
+  let libraries = libraries_dir(minecraft_dir);
+  walk_files(&libraries, &mut |path, meta| {
+    let relative = path.strip_prefix(&libraries).unwrap_or(path);
+    if !refs.libraries.contains_key(relative) && !is_protected_library(relative) {
+      orphans.push((path.to_path_buf(), meta.len()));
+    }
+  })?;
+
+  walk_files(&indexes_dir(minecraft_dir), &mut |path, meta| {
+    let id = path
+      .file_stem()
+      .and_then(|s| s.to_str())
+      .unwrap_or_default();
+    if !refs.asset_indexes.contains(id) {
+      orphans.push((path.to_path_buf(), meta.len()));
+    }
+  })?;
+
+  if !refs.objects_incomplete {
+    walk_files(&objects_dir(minecraft_dir), &mut |path, meta| {
+      let hash = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+      if !refs.objects.contains(hash) {
+        orphans.push((path.to_path_buf(), meta.len()));
+      }
+    })?;
+  }
+  Ok(orphans)
+}
+
+/// Removes empty directories below `dir`, leaving `dir` itself in place.
+fn prune_empty_dirs(dir: &Path) -> io::Result<()> {
+  if !dir.is_dir() {
+    return Ok(());
+  }
+  for entry in fs::read_dir(dir)? {
+    let entry = entry?;
+    if entry.file_type()?.is_dir() {
+      let path = entry.path();
+      prune_empty_dirs(&path)?;
+      if fs::read_dir(&path)?.next().is_none() {
+        fs::remove_dir(&path)?;
+      }
+    }
+  }
+  Ok(())
+}
+
+/// Deletes every orphaned library, asset index and asset object. Files that
+/// cannot be deleted are counted and skipped; it only fails if there were
+/// orphans and none of them could be removed.
+pub fn collect_garbage(minecraft_dir: &Path) -> Result<GcReport> {
+  let refs = collect_references(minecraft_dir, None)?;
+  let mut report = GcReport::default();
+  let mut first_error = None;
+  for (path, size) in find_orphans(minecraft_dir, &refs)? {
+    match fs::remove_file(&path) {
+      Ok(()) => {
+        report.removed_files += 1;
+        report.freed_bytes += size;
+      }
+      Err(e) => {
+        log::warn!("cannot delete {}: {e}", path.display());
+        report.failed_files += 1;
+        first_error.get_or_insert(e);
+      }
+    }
+  }
+  if let (0, Some(e)) = (report.removed_files, first_error) {
+    return Err(e.into());
+  }
+
+  for dir in [libraries_dir(minecraft_dir), objects_dir(minecraft_dir)] {
+    if let Err(e) = prune_empty_dirs(&dir) {
+      log::warn!("cannot prune {}: {e}", dir.display());
+    }
+  }
+  log::info!(
+    "garbage collection removed {} files, freed {} bytes, {} failed",
+    report.removed_files,
+    report.freed_bytes,
+    report.failed_files
+  );
+  Ok(report)
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+  pub checked_files: u64,
+  pub corrupt: Vec<PathBuf>,
+  /// Files that could not be hashed, e.g. locked by another process.
+  pub unreadable: Vec<PathBuf>,
+  pub orphaned: Vec<PathBuf>,
+  /// Set when an asset index is missing or unreadable. Asset objects are then
+  /// left out of `orphaned`, since any of them might still be needed.
+  pub objects_incomplete: bool,
+}
+
+impl IntegrityReport {
+  fn check(&mut self, path: PathBuf, expected: &str) {
+    self.checked_files += 1;
+    match sha1_file(&path) {
+      Ok(actual) if actual.eq_ignore_ascii_case(expected) => {}
+      Ok(_) => self.corrupt.push(path),
+      Err(e) => {
+        log::warn!("cannot hash {}: {e}", path.display());
+        self.unreadable.push(path);
+      }
+    }
+  }
+}
+
+fn scan_integrity_blocking(minecraft_dir: &Path) -> Result<IntegrityReport> {
+  let refs = collect_references(minecraft_dir, None)?;
+  let mut report = IntegrityReport {
+    orphaned: find_orphans(minecraft_dir, &refs)?
+      .into_iter()
+      .map(|(path, _)| path)
+      .collect(),
+    objects_incomplete: refs.objects_incomplete,
+    ..Default::default()
+  };
+
+  let libraries = libraries_dir(minecraft_dir);
+  for (relative, sha1) in &refs.libraries {
+    let Some(sha1) = sha1 else {
+      continue;
+    };
+    let path = libraries.join(relative);
+    if !path.is_file() {
+      continue;
+    }
+    report.check(path, sha1);
+  }
+
+  // Asset objects are named after their own hash, so they check themselves.
+  let mut objects = Vec::new();
+  walk_files(&objects_dir(minecraft_dir), &mut |path, _| {
+    objects.push(path.to_path_buf());
+  })?;
+  for path in objects {
+    let Some(hash) = path.file_name().and_then(|s| s.to_str()) else {
+      continue;
+    };
+    if hash.len() != 40 {
+      continue;
+    }
+    let hash = hash.to_string();
+    report.check(path, &hash);
+  }
+
+  report.corrupt.sort();
+  report.unreadable.sort();
+  report.orphaned.sort();
+  Ok(report)
+}
+
+/// Verifies libraries and asset objects against the installed versions and
+/// lists files that are corrupt or no longer referenced.
+#[tauri::command]
+pub async fn scan_integrity(minecraft_dir: String) -> Result<IntegrityReport> {
+  tauri::async_runtime::spawn_blocking(move || scan_integrity_blocking(Path::new(&minecraft_dir)))
+    .await?
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryStats {
+  pub files: u64,
+  pub bytes: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+  pub libraries: CategoryStats,
+  pub asset_objects: CategoryStats,
+  pub asset_indexes: CategoryStats,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheProgress {
+  scanned_files: u64,
+  scanned_bytes: u64,
+}
+
+fn cache_stats_blocking(app: &AppHandle, minecraft_dir: &Path) -> Result<CacheStats> {
+  let mut stats = CacheStats::default();
+  let mut progress = CacheProgress {
+    scanned_files: 0,
+    scanned_bytes: 0,
+  };
+
+  let categories = [
+    (libraries_dir(minecraft_dir), &mut stats.libraries),
+    (objects_dir(minecraft_dir), &mut stats.asset_objects),
+    (indexes_dir(minecraft_dir), &mut stats.asset_indexes),
+  ];
+  for (dir, category) in categories {
+    walk_files(&dir, &mut |_, meta| {
+      category.files += 1;
+      category.bytes += meta.len();
+      progress.scanned_files += 1;
+      progress.scanned_bytes += meta.len();
+      if progress.scanned_files % PROGRESS_INTERVAL == 0 {
+        let _ = app.emit("cache://progress", progress);
+      }
+    })?;
+  }
+  let _ = app.emit("cache://progress", progress);
+  Ok(stats)
+}
+
+/// Size and file count of the shared library and asset stores. Emits
+/// `cache://progress` while walking, since large caches take a while.
+#[tauri::command]
+pub async fn cache_stats(app: AppHandle, minecraft_dir: String) -> Result<CacheStats> {
+  tauri::async_runtime::spawn_blocking(move || {
+    cache_stats_blocking(&app, Path::new(&minecraft_dir))
+  })
+  .await?
+}
+
+/// Removes libraries and assets no installed version references.
+#[tauri::command]
+pub async fn clear_unused_cache(minecraft_dir: String) -> Result<GcReport> {
+  tauri::async_runtime::spawn_blocking(move || collect_garbage(Path::new(&minecraft_dir))).await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::versions::tests::install;
+  use serde_json::json;
+
+  fn library(path: &str) -> Value {
+    json!({ "downloads": { "artifact": { "path": path } } })
+  }
+
+  fn touch(path: &Path) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, b"data").unwrap();
+  }
+
+  #[test]
+  fn keeps_libraries_another_version_uses() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    let libraries = libraries_dir(mc);
+    let shared = library("com/shared/shared.jar");
+    install(
+      mc,
+      "a",
+      json!({ "libraries": [shared, library("com/only-a/only-a.jar")] }),
+    );
+    install(mc, "b", json!({ "libraries": [shared] }));
+    touch(&libraries.join("com/shared/shared.jar"));
+    touch(&libraries.join("com/only-a/only-a.jar"));
+
+    // As delete_version does before collecting.
+    fs::remove_dir_all(versions::version_dir(mc, "a")).unwrap();
+    let report = collect_garbage(mc).unwrap();
+
+    assert_eq!(report.removed_files, 1);
+    assert!(libraries.join("com/shared/shared.jar").is_file());
+    assert!(!libraries.join("com/only-a").exists());
+  }
+
+  #[test]
+  fn keeps_forge_processor_outputs() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    let libraries = libraries_dir(mc);
+    install(mc, "forge", json!({ "libraries": [] }));
+    let srg = libraries.join("net/minecraft/client/1.20.1-20230612.114412/client-srg.jar");
+    let forge = libraries.join("net/minecraftforge/forge/1.20.1-47.1.0/forge-client.jar");
+    touch(&srg);
+    touch(&forge);
+
+    assert_eq!(collect_garbage(mc).unwrap().removed_files, 0);
+    assert!(srg.is_file());
+    assert!(forge.is_file());
+  }
+
+  #[test]
+  fn keeps_objects_when_an_index_is_missing() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(mc, "1.20.1", json!({ "assetIndex": { "id": "5" } }));
+    let object = object_path(mc, "a94a8fe5ccb19ba61c4c0873d391e987982fbbd3");
+    touch(&object);
+
+    collect_garbage(mc).unwrap();
+
+    assert!(object.is_file());
+  }
+
+  #[test]
+  fn removes_objects_no_index_lists() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(mc, "1.20.1", json!({ "assetIndex": { "id": "5" } }));
+    let index =
+      json!({ "objects": { "a": { "hash": "a94a8fe5ccb19ba61c4c0873d391e987982fbbd3" } } });
+    fs::create_dir_all(indexes_dir(mc)).unwrap();
+    fs::write(indexes_dir(mc).join("5.json"), index.to_string()).unwrap();
+    let kept = object_path(mc, "a94a8fe5ccb19ba61c4c0873d391e987982fbbd3");
+    let orphan = object_path(mc, "0000000000000000000000000000000000000000");
+    touch(&kept);
+    touch(&orphan);
+
+    collect_garbage(mc).unwrap();
+
+    assert!(kept.is_file());
+    assert!(!orphan.exists());
+  }
+
+  #[test]
+  fn aborts_when_a_version_json_is_malformed() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    fs::create_dir_all(versions::version_dir(mc, "broken")).unwrap();
+    fs::write(versions::version_json_path(mc, "broken"), "{").unwrap();
+    let library = libraries_dir(mc).join("com/example/example.jar");
+    touch(&library);
+
+    let err = collect_garbage(mc).unwrap_err();
+
+    assert!(matches!(err, LauncherError::MalformedJson { .. }));
+    assert!(library.is_file());
+  }
+
+  // macOS file systems reject names that are not valid UTF-8.
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn aborts_when_a_version_folder_name_is_not_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    let name = OsStr::from_bytes(b"caf\xe9");
+    let dir = versions::versions_dir(mc).join(name);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(OsStr::from_bytes(b"caf\xe9.json")), "{}").unwrap();
+    let library = libraries_dir(mc).join("com/example/example.jar");
+    touch(&library);
+
+    let err = collect_garbage(mc).unwrap_err();
+
+    assert!(matches!(err, LauncherError::InvalidVersionId(_)));
+    assert!(library.is_file());
+  }
+
+  #[test]
+  fn refuses_to_collect_without_a_versions_folder() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    let library = libraries_dir(mc).join("com/example/example.jar");
+    let object = object_path(mc, "a17c9aaa61e80a1bf71d0d850af4e5baa9800bbd");
+    touch(&library);
+    touch(&object);
+
+    let err = collect_garbage(mc).unwrap_err();
+
+    assert!(
+      matches!(err, LauncherError::NotADirectory(path) if path == versions::versions_dir(mc))
+    );
+    assert!(library.is_file());
+    assert!(object.is_file());
+  }
+
+  #[test]
+  fn scan_reports_corrupt_objects() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    fs::create_dir_all(versions::versions_dir(mc)).unwrap();
+    // SHA1 of "data".
+    let good = object_path(mc, "a17c9aaa61e80a1bf71d0d850af4e5baa9800bbd");
+    let bad = object_path(mc, "0000000000000000000000000000000000000000");
+    touch(&good);
+    touch(&bad);
+
+    let report = scan_integrity_blocking(mc).unwrap();
+
+    assert_eq!(report.checked_files, 2);
+    assert_eq!(report.corrupt, [bad]);
+    assert!(report.unreadable.is_empty());
+  }
+
+  #[test]
+  fn scan_flags_incomplete_orphans_when_an_index_is_missing() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(mc, "1.20.1", json!({ "assetIndex": { "id": "5" } }));
+    touch(&object_path(mc, "a17c9aaa61e80a1bf71d0d850af4e5baa9800bbd"));
+
+    let report = scan_integrity_blocking(mc).unwrap();
+
+    assert!(report.objects_incomplete);
+    assert!(report.orphaned.is_empty());
+  }
+}
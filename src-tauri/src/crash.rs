@@ -0,0 +1,342 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::Result;
+
+/// Transformer module ids that belong to the game or the loader, never the culprit.
+const IGNORED_MODULES: &[&str] = &[
+  "minecraft",
+  "mcp",
+  "forge",
+  "neoforge",
+  "fml",
+  "fmlcore",
+  "javafmllanguage",
+  "mixin",
+  "modlauncher",
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashSummary {
+  pub file: PathBuf,
+  pub description: Option<String>,
+  pub exception: Option<String>,
+  pub culprit_mod: Option<String>,
+  pub minecraft_version: Option<String>,
+  pub loader: Option<String>,
+}
+
+/// Lowercased file stems of the jars in `<game_dir>/mods`.
+fn mod_jars(game_dir: &Path) -> Result<HashSet<String>> {
+  let dir = game_dir.join("mods");
+  if !dir.is_dir() {
+    return Ok(HashSet::new());
+  }
+  let mut jars = HashSet::new();
+  for entry in fs::read_dir(dir)? {
+    let name = entry?.file_name().to_string_lossy().to_ascii_lowercase();
+    if let Some(stem) = name.strip_suffix(".jar") {
+      jars.insert(stem.to_string());
+    }
+  }
+  Ok(jars)
+}
+
+/// Parses the newest report in `<game_dir>/crash-reports`, if there is one.
+pub fn parse_latest(game_dir: &Path) -> Result<Option<CrashSummary>> {
+  let dir = game_dir.join("crash-reports");
+  if !dir.is_dir() {
+    return Ok(None);
+  }
+
+  let mut latest: Option<(std::time::SystemTime, PathBuf)> = None;
+  for entry in fs::read_dir(&dir)? {
+    let entry = entry?;
+    let path = entry.path();
+    if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+      continue;
+    }
+    let modified = entry.metadata()?.modified()?;
+    if latest.as_ref().map_or(true, |(t, _)| modified > *t) {
+      latest = Some((modified, path));
+    }
+  }
+
+  match latest {
+    Some((_, path)) => {
+      // Reports can contain non-UTF-8 bytes from mod names or OS details.
+      let text = String::from_utf8_lossy(&fs::read(&path)?).into_owned();
+      Ok(Some(parse_report(path, &text, &mod_jars(game_dir)?)))
+    }
+    None => Ok(None),
+  }
+}
+
+/// `mod_jars` holds the lowercased file stems of the jars in `mods/`; only
+/// stacktrace frames from those jars can name the culprit.
+pub fn parse_report(file: PathBuf, text: &str, mod_jars: &HashSet<String>) -> CrashSummary {
+  let lines: Vec<&str> = text.lines().collect();
+
+  let description = field(&lines, "Description");
+  let exception = exception_class(&lines);
+  let culprit_mod = suspected_mod(&lines)
+    .or_else(|| mod_section(&lines))
+    .or_else(|| stacktrace_culprit(&lines, mod_jars));
+  let minecraft_version = field(&lines, "Minecraft Version");
+  let loader = loader(&lines);
+
+  CrashSummary {
+    file,
+    description,
+    exception,
+    culprit_mod,
+    minecraft_version,
+    loader,
+  }
+}
+
+/// Value of the first `Key: value` line, with surrounding tabs trimmed.
+fn field(lines: &[&str], key: &str) -> Option<String> {
+  lines.iter().find_map(|line| {
+    let rest = line.trim().strip_prefix(key)?.strip_prefix(':')?;
+    let value = rest.trim();
+    (!value.is_empty()).then(|| value.to_string())
+  })
+}
+
+/// The exception is the first non-blank line after `Description:`.
+fn exception_class(lines: &[&str]) -> Option<String> {
+  let start = lines.iter().position(|l| l.starts_with("Description:"))?;
+  let line = lines[start + 1..]
+    .iter()
+    .map(|l| l.trim())
+    .find(|l| !l.is_empty())?;
+  let class = line.split(':').next()?.trim();
+  (!class.is_empty() && !class.contains(' ')).then(|| class.to_string())
+}
+
+/// Forge 1.12 and earlier write `Suspected Mod(s): Name (modid), Version: x`.
+fn suspected_mod(lines: &[&str]) -> Option<String> {
+  lines.iter().find_map(|line| {
+    let line = line.trim();
+    let rest = line
+      .strip_prefix("Suspected Mods:")
+      .or_else(|| line.strip_prefix("Suspected Mod:"))?;
+    let value = rest.trim();
+    (!value.is_empty() && value != "NONE" && !value.starts_with("Unknown"))
+      .then(|| value.to_string())
+  })
+}
+
+/// Modern Forge adds a `-- MOD <modid> --` section for the failing mod.
+fn mod_section(lines: &[&str]) -> Option<String> {
+  lines.iter().find_map(|line| {
+    let id = line.trim().strip_prefix("-- MOD ")?.strip_suffix(" --")?;
+    Some(id.trim().to_string())
+  })
+}
+
+/// Walks the first stacktrace looking for a frame from a jar in `mods/`
+/// (`~[sodium-fabric.jar:?]`) or a Forge transformer (`TRANSFORMER/modid@1.0`).
+/// Jars outside `mods/` are the game itself, the loader or libraries such as
+/// LWJGL and Guava.
+fn stacktrace_culprit(lines: &[&str], mod_jars: &HashSet<String>) -> Option<String> {
+  lines
+    .iter()
+    .map(|l| l.trim())
+    .skip_while(|l| !l.starts_with("at "))
+    .take_while(|l| l.starts_with("at ") || l.starts_with("Caused by") || l.starts_with("..."))
+    .filter(|l| l.starts_with("at "))
+    .find_map(|frame| frame_source(frame, mod_jars))
+}
+
+fn frame_source(frame: &str, mod_jars: &HashSet<String>) -> Option<String> {
+  if let Some(idx) = frame.find("TRANSFORMER/") {
+    let rest = &frame[idx + "TRANSFORMER/".len()..];
+    let module = rest.split(['@', '/', '}']).next()?.trim();
+    let ignored =
+      module.is_empty() || IGNORED_MODULES.contains(&module.to_ascii_lowercase().as_str());
+    return (!ignored).then(|| module.to_string());
+  }
+
+  let start = frame.rfind('[')? + 1;
+  let jar = frame[start..].split([':', ']']).next()?;
+  // Modern Forge appends the jar's position in the module layer, as in
+  // `create-0.5.1.jar%23191!/`.
+  let jar = jar.split("%23").next()?.trim();
+  let name = jar.strip_suffix(".jar")?;
+  mod_jars
+    .contains(&name.to_ascii_lowercase())
+    .then(|| name.to_string())
+}
+
+fn loader(lines: &[&str]) -> Option<String> {
+  if let Some(version) = field(lines, "NeoForge") {
+    return Some(format!("NeoForge {version}"));
+  }
+  if let Some(version) = field(lines, "Forge") {
+    // e.g. `Forge: net.minecraftforge:47.1.0`
+    let version = version.rsplit(':').next().unwrap_or(&version).to_string();
+    return Some(format!("Forge {version}"));
+  }
+  if let Some(entry) = field(lines, "fabricloader") {
+    // e.g. `fabricloader: Fabric Loader 0.14.21`
+    return Some(entry);
+  }
+  if let Some(entry) = field(lines, "quilt_loader") {
+    return Some(entry);
+  }
+  field(lines, "Client Brand").filter(|brand| brand != "vanilla")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(text: &str, mods: &[&str]) -> CrashSummary {
+    let mods = mods.iter().map(|m| m.to_ascii_lowercase()).collect();
+    parse_report(PathBuf::from("crash.txt"), text, &mods)
+  }
+
+  const FABRIC: &str = "---- Minecraft Crash Report ----
+// Ooh. Shiny.
+
+Time: 2023-09-02 21:14:07
+Description: Rendering overlay
+
+java.lang.IllegalStateException: Region not loaded
+\tat com.google.common.base.Preconditions.checkState(Preconditions.java:512) ~[guava-31.1-jre.jar:?]
+\tat me.jellysquid.mods.sodium.client.render.chunk.RenderSectionManager.update(RenderSectionManager.java:171) ~[sodium-fabric-mc1.20.1-0.5.3.jar:?]
+\tat net.minecraft.class_761.method_22710(class_761.java:1168) ~[client-intermediary.jar:?]
+\tat net.fabricmc.loader.impl.game.minecraft.MinecraftGameProvider.launch(MinecraftGameProvider.java:470) ~[fabric-loader-0.14.22.jar:?]
+
+-- System Details --
+Details:
+\tMinecraft Version: 1.20.1
+\tFabric Mods: 
+\t\tfabricloader: Fabric Loader 0.14.22
+\t\tsodium: Sodium 0.5.3
+";
+
+  const FORGE: &str = "---- Minecraft Crash Report ----
+// Surprise! Haha. Well, this is awkward.
+
+Time: 2023-10-11 19:02:45
+Description: Unexpected error
+
+java.lang.NullPointerException: Cannot invoke \"net.minecraft.world.level.Level.m_46472_()\" because \"p_109600_\" is null
+\tat net.minecraft.client.renderer.LevelRenderer.m_109599_(LevelRenderer.java:1376) ~[client-1.20.1-20230612.114412-srg.jar%23183!/:?] {re:mixin,pl:accesstransformer:B,re:classloading}
+\tat net.minecraft.client.renderer.GameRenderer.m_109089_(GameRenderer.java:1102) ~[client-1.20.1-20230612.114412-srg.jar%23183!/:?] {re:mixin,pl:accesstransformer:B}
+\tat org.embeddedt.embeddium.impl.render.EmbeddiumWorldRenderer.setupTerrain(EmbeddiumWorldRenderer.java:179) ~[embeddium-0.2.10+mc1.20.1.jar%23196!/:0.2.10+mc1.20.1] {re:classloading}
+\tat net.minecraftforge.fml.loading.targets.CommonLaunchHandler.runTarget(CommonLaunchHandler.java:111) ~[fmlloader-1.20.1-47.1.0.jar%2369!/:?] {}
+
+-- System Details --
+Details:
+\tMinecraft Version: 1.20.1
+\tForge: net.minecraftforge:47.1.0
+";
+
+  const VANILLA: &str = "---- Minecraft Crash Report ----
+// This doesn't make any sense!
+
+Time: 2023-03-08 09:30:12
+Description: Initializing game
+
+java.lang.IllegalStateException: GLFW error before init: [0x10008]Cocoa: Failed to find service port for display
+\tat com.mojang.blaze3d.platform.GLX.lambda$_initGlfw$0(GLX.java:81) ~[1.19.3.jar:?]
+\tat org.lwjgl.glfw.GLFWErrorCallbackI.callback(GLFWErrorCallbackI.java:43) ~[lwjgl-glfw-3.3.1.jar:build 7]
+\tat org.lwjgl.glfw.GLFW.glfwInit(GLFW.java:1046) ~[lwjgl-3.3.1.jar:build 7]
+\tat net.minecraft.client.main.Main.main(Main.java:176) ~[1.19.3.jar:?]
+
+-- System Details --
+Details:
+\tMinecraft Version: 1.19.3
+\tClient Brand: vanilla
+";
+
+  #[test]
+  fn fabric_culprit_skips_libraries_and_the_game() {
+    let summary = parse(
+      FABRIC,
+      &["sodium-fabric-mc1.20.1-0.5.3", "fabric-api-0.88.1"],
+    );
+    assert_eq!(summary.description.as_deref(), Some("Rendering overlay"));
+    assert_eq!(
+      summary.exception.as_deref(),
+      Some("java.lang.IllegalStateException")
+    );
+    assert_eq!(
+      summary.culprit_mod.as_deref(),
+      Some("sodium-fabric-mc1.20.1-0.5.3")
+    );
+    assert_eq!(summary.minecraft_version.as_deref(), Some("1.20.1"));
+    assert_eq!(summary.loader.as_deref(), Some("Fabric Loader 0.14.22"));
+  }
+
+  #[test]
+  fn forge_culprit_strips_the_module_suffix() {
+    let summary = parse(FORGE, &["Embeddium-0.2.10+mc1.20.1"]);
+    assert_eq!(
+      summary.culprit_mod.as_deref(),
+      Some("embeddium-0.2.10+mc1.20.1")
+    );
+    assert_eq!(summary.loader.as_deref(), Some("Forge 47.1.0"));
+  }
+
+  #[test]
+  fn forge_without_mod_frames_has_no_culprit() {
+    assert_eq!(parse(FORGE, &[]).culprit_mod, None);
+  }
+
+  #[test]
+  fn vanilla_has_no_culprit_or_loader() {
+    let summary = parse(VANILLA, &[]);
+    assert_eq!(summary.culprit_mod, None);
+    assert_eq!(summary.loader, None);
+    assert_eq!(summary.description.as_deref(), Some("Initializing game"));
+  }
+
+  #[test]
+  fn prefers_the_mod_section() {
+    let text = FORGE.replace(
+      "-- System Details --",
+      "-- MOD embeddium --\n\n-- System Details --",
+    );
+    assert_eq!(parse(&text, &[]).culprit_mod.as_deref(), Some("embeddium"));
+  }
+
+  #[test]
+  fn transformer_frames_name_the_mod() {
+    let text = "Description: Ticking entity\n\njava.lang.NullPointerException\n\
+      \tat TRANSFORMER/minecraft@1.18.2/net.minecraft.world.level.Level.m_46653_(Level.java:479)\n\
+      \tat TRANSFORMER/create@0.5.0/com.simibubi.create.Create.tick(Create.java:12)\n";
+    assert_eq!(parse(text, &[]).culprit_mod.as_deref(), Some("create"));
+  }
+
+  #[test]
+  fn parse_latest_reads_mods_dir() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::create_dir_all(tmp.path().join("crash-reports")).unwrap();
+    fs::create_dir_all(tmp.path().join("mods")).unwrap();
+    fs::write(
+      tmp.path().join("crash-reports/crash-2023-09-02.txt"),
+      FABRIC,
+    )
+    .unwrap();
+    fs::write(
+      tmp.path().join("mods/sodium-fabric-mc1.20.1-0.5.3.jar"),
+      b"",
+    )
+    .unwrap();
+
+    let summary = parse_latest(tmp.path()).unwrap().unwrap();
+    assert_eq!(
+      summary.culprit_mod.as_deref(),
+      Some("sodium-fabric-mc1.20.1-0.5.3")
+    );
+  }
+}
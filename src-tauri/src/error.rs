@@ -0,0 +1,84 @@
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LauncherError {
+  #[error("{0}")]
+  Io(#[from] io::Error),
+
+  #[error("{0}")]
+  Json(#[from] serde_json::Error),
+
+  #[error("{}: {source}", path.display())]
+  MalformedJson {
+    path: PathBuf,
+    source: serde_json::Error,
+  },
+
+  #[error("{}: {source}", path.display())]
+  MalformedNbt { path: PathBuf, source: io::Error },
+
+  #[error("{0}")]
+  Tauri(#[from] tauri::Error),
+
+  #[error("invalid version id: {0:?}")]
+  InvalidVersionId(String),
+
+  #[error("version {0} is not installed")]
+  VersionNotInstalled(String),
+
+  #[error("version {0} already exists")]
+  VersionExists(String),
+
+  #[error("version {child} inherits from {version}")]
+  VersionInUse { version: String, child: String },
+
+  #[error("{} is not a directory", .0.display())]
+  NotADirectory(PathBuf),
+
+  #[error("invalid game option: {0:?}")]
+  InvalidGameOption(String),
+
+  #[error("invalid server address: {0:?}")]
+  InvalidServerAddress(String),
+}
+
+pub type Result<T, E = LauncherError> = std::result::Result<T, E>;
+
+impl LauncherError {
+  /// Stable identifier the frontend can match on or localize.
+  pub fn kind(&self) -> &'static str {
+    match self {
+      Self::Io(_) => "io",
+      Self::Json(_) | Self::MalformedJson { .. } => "json",
+      Self::MalformedNbt { .. } => "nbt",
+      Self::Tauri(_) => "tauri",
+      Self::InvalidVersionId(_) => "invalidVersionId",
+      Self::VersionNotInstalled(_) => "versionNotInstalled",
+      Self::VersionExists(_) => "versionExists",
+      Self::VersionInUse { .. } => "versionInUse",
+      Self::NotADirectory(_) => "notADirectory",
+      Self::InvalidGameOption(_) => "invalidGameOption",
+      Self::InvalidServerAddress(_) => "invalidServerAddress",
+    }
+  }
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+  kind: &'a str,
+  message: String,
+}
+
+/// Commands reject with `{ kind, message }`.
+impl Serialize for LauncherError {
+  fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    ErrorBody {
+      kind: self.kind(),
+      message: self.to_string(),
+    }
+    .serialize(serializer)
+  }
+}
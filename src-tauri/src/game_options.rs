@@ -0,0 +1,273 @@
+use std::fs;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::{LauncherError, Result};
+use crate::nbt::{self, Tag};
+
+const OPTIONS_FILE: &str = "options.txt";
+const SERVERS_FILE: &str = "servers.dat";
+
+/// NBT type id of a compound, used for the `servers` list.
+const COMPOUND: u8 = 10;
+
+fn ensure_game_dir(game_dir: &Path) -> Result<()> {
+  if game_dir.is_dir() {
+    Ok(())
+  } else {
+    Err(LauncherError::NotADirectory(game_dir.to_path_buf()))
+  }
+}
+
+/// Writes next to `path` and renames over it, so the game never sees a
+/// half-written file.
+fn write_atomic(
+  path: &Path,
+  write: impl FnOnce(&mut BufWriter<fs::File>) -> io::Result<()>,
+) -> Result<()> {
+  let mut tmp = path.as_os_str().to_owned();
+  tmp.push(".tmp");
+  let tmp = PathBuf::from(tmp);
+
+  let result = (|| {
+    let mut out = BufWriter::new(fs::File::create(&tmp)?);
+    write(&mut out)?;
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    fs::rename(&tmp, path)
+  })();
+  if result.is_err() {
+    let _ = fs::remove_file(&tmp);
+  }
+  Ok(result?)
+}
+
+/// `options.txt` as `key:value` pairs in file order. Lines without a colon
+/// are skipped.
+pub fn read_options(game_dir: &Path) -> Result<Vec<(String, String)>> {
+  let path = game_dir.join(OPTIONS_FILE);
+  if !path.is_file() {
+    return Ok(Vec::new());
+  }
+  let text = fs::read_to_string(path)?;
+  Ok(
+    text
+      .lines()
+      .filter_map(|line| line.split_once(':'))
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+      .collect(),
+  )
+}
+
+fn set_game_option_blocking(game_dir: &Path, key: &str, value: &str) -> Result<()> {
+  ensure_game_dir(game_dir)?;
+  if key.is_empty() || key.contains([':', '\n', '\r']) {
+    return Err(LauncherError::InvalidGameOption(key.to_string()));
+  }
+  if value.contains(['\n', '\r']) {
+    return Err(LauncherError::InvalidGameOption(format!("{key}:{value}")));
+  }
+
+  let path = game_dir.join(OPTIONS_FILE);
+  let text = if path.is_file() {
+    fs::read_to_string(&path)?
+  } else {
+    String::new()
+  };
+
+  // Rewrite in place so the game's own ordering and any lines we do not
+  // understand survive.
+  let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+  let entry = format!("{key}:{value}");
+  match lines
+    .iter_mut()
+    .find(|line| line.split_once(':').is_some_and(|(k, _)| k == key))
+  {
+    Some(line) => *line = entry,
+    None => lines.push(entry),
+  }
+
+  write_atomic(&path, |out| {
+    for line in &lines {
+      writeln!(out, "{line}")?;
+    }
+    Ok(())
+  })
+}
+
+/// Sets `key` in the game directory's `options.txt`, creating the file if the
+/// game has not been started yet. Minecraft fills in every other option with
+/// its defaults on first launch.
+#[tauri::command]
+pub async fn set_game_option(game_dir: String, key: String, value: String) -> Result<()> {
+  tauri::async_runtime::spawn_blocking(move || {
+    set_game_option_blocking(Path::new(&game_dir), &key, &value)
+  })
+  .await?
+}
+
+fn read_servers(path: &Path) -> Result<Tag> {
+  if !path.is_file() {
+    return Ok(Tag::Compound(Vec::new()));
+  }
+  let malformed = |source| LauncherError::MalformedNbt {
+    path: path.to_path_buf(),
+    source,
+  };
+  let (_, root) = nbt::read(&mut BufReader::new(fs::File::open(path)?)).map_err(malformed)?;
+  if !matches!(root, Tag::Compound(_)) {
+    return Err(malformed(io::Error::new(
+      io::ErrorKind::InvalidData,
+      "root tag is not a compound",
+    )));
+  }
+  Ok(root)
+}
+
+fn add_server_blocking(game_dir: &Path, name: &str, address: &str) -> Result<bool> {
+  ensure_game_dir(game_dir)?;
+  let address = address.trim();
+  if address.is_empty() {
+    return Err(LauncherError::InvalidServerAddress(address.to_string()));
+  }
+
+  let path = game_dir.join(SERVERS_FILE);
+  let mut root = read_servers(&path)?;
+
+  if root.get("servers").is_none() {
+    if let Tag::Compound(entries) = &mut root {
+      entries.push(("servers".to_string(), Tag::List(COMPOUND, Vec::new())));
+    }
+  }
+  let servers = match root.get_mut("servers") {
+    // The game writes an empty list with element type TAG_End.
+    Some(Tag::List(element, servers)) if *element == COMPOUND || servers.is_empty() => {
+      *element = COMPOUND;
+      servers
+    }
+    _ => {
+      return Err(LauncherError::MalformedNbt {
+        path,
+        source: io::Error::new(
+          io::ErrorKind::InvalidData,
+          "servers is not a list of compounds",
+        ),
+      })
+    }
+  };
+
+  if servers
+    .iter()
+    .any(|server| server.get("ip").and_then(Tag::as_str) == Some(address))
+  {
+    return Ok(false);
+  }
+  servers.push(Tag::Compound(vec![
+    ("name".to_string(), Tag::String(name.to_string())),
+    ("ip".to_string(), Tag::String(address.to_string())),
+  ]));
+
+  write_atomic(&path, |out| nbt::write(out, "", &root))?;
+  Ok(true)
+}
+
+/// Appends a server to the multiplayer list. Returns `false` without touching
+/// the file when a server with the same address is already listed.
+#[tauri::command]
+pub async fn add_server(game_dir: String, name: String, address: String) -> Result<bool> {
+  tauri::async_runtime::spawn_blocking(move || {
+    add_server_blocking(Path::new(&game_dir), &name, &address)
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn write_servers(game_dir: &Path, root: &Tag) {
+    let mut bytes = Vec::new();
+    nbt::write(&mut bytes, "", root).unwrap();
+    fs::write(game_dir.join(SERVERS_FILE), bytes).unwrap();
+  }
+
+  fn server_ips(game_dir: &Path) -> Vec<String> {
+    match read_servers(&game_dir.join(SERVERS_FILE))
+      .unwrap()
+      .get("servers")
+    {
+      Some(Tag::List(COMPOUND, servers)) => servers
+        .iter()
+        .map(|server| server.get("ip").and_then(Tag::as_str).unwrap().to_string())
+        .collect(),
+      other => panic!("unexpected servers tag: {other:?}"),
+    }
+  }
+
+  #[test]
+  fn set_option_replaces_in_place_and_keeps_unknown_lines() {
+    let tmp = tempfile::tempdir().unwrap();
+    let game_dir = tmp.path();
+    fs::write(
+      game_dir.join(OPTIONS_FILE),
+      "version:3465\r\n# not an option\r\nlang:en_us\r\nfov:0.0\r\n",
+    )
+    .unwrap();
+
+    set_game_option_blocking(game_dir, "lang", "de_de").unwrap();
+
+    assert_eq!(
+      fs::read_to_string(game_dir.join(OPTIONS_FILE)).unwrap(),
+      "version:3465\n# not an option\nlang:de_de\nfov:0.0\n"
+    );
+  }
+
+  #[test]
+  fn set_option_creates_the_file() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    set_game_option_blocking(tmp.path(), "lang", "de_de").unwrap();
+
+    assert_eq!(
+      read_options(tmp.path()).unwrap(),
+      [("lang".to_string(), "de_de".to_string())]
+    );
+  }
+
+  #[test]
+  fn add_server_skips_a_listed_address() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    assert!(add_server_blocking(tmp.path(), "Example", "mc.example.org").unwrap());
+    assert!(!add_server_blocking(tmp.path(), "Again", " mc.example.org ").unwrap());
+    assert!(add_server_blocking(tmp.path(), "Other", "other.example.org").unwrap());
+
+    assert_eq!(
+      server_ips(tmp.path()),
+      ["mc.example.org", "other.example.org"]
+    );
+  }
+
+  #[test]
+  fn add_server_upgrades_an_empty_end_list() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_servers(
+      tmp.path(),
+      // What the game writes once every server has been removed.
+      &Tag::Compound(vec![("servers".into(), Tag::List(0, Vec::new()))]),
+    );
+
+    assert!(add_server_blocking(tmp.path(), "Example", "mc.example.org").unwrap());
+
+    assert_eq!(server_ips(tmp.path()), ["mc.example.org"]);
+  }
+
+  #[test]
+  fn add_server_rejects_a_non_compound_root() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_servers(tmp.path(), &Tag::List(COMPOUND, Vec::new()));
+
+    let err = add_server_blocking(tmp.path(), "Example", "mc.example.org").unwrap_err();
+
+    assert!(matches!(err, LauncherError::MalformedNbt { .. }), "{err:?}");
+  }
+}
@@ -1,3 +1,14 @@
+pub mod cache;
+pub mod crash;
+pub mod error;
+pub mod game_options;
+pub mod nbt;
+pub mod readiness;
+pub mod versions;
+pub mod window_state;
+
+use tauri::Manager;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
@@ -9,8 +20,35 @@ pub fn run() {
             .build(),
         )?;
       }
+      if let Some(window) = app.get_webview_window(window_state::MAIN_WINDOW) {
+        window_state::restore(&window);
+      }
       Ok(())
     })
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .on_window_event(|window, event| {
+      if let tauri::WindowEvent::CloseRequested { .. } = event {
+        if window.label() == window_state::MAIN_WINDOW {
+          window_state::save(window);
+        }
+      }
+    })
+    .invoke_handler(tauri::generate_handler![
+      cache::cache_stats,
+      cache::clear_unused_cache,
+      cache::scan_integrity,
+      game_options::add_server,
+      game_options::set_game_option,
+      readiness::can_launch_offline,
+      versions::delete_version,
+      versions::list_installed_versions,
+      versions::rename_version,
+      versions::version_size,
+    ])
+    .build(tauri::generate_context!())
+    .expect("error while building tauri application")
+    .run(|app, event| {
+      if let tauri::RunEvent::Exit = event {
+        window_state::save_main(app);
+      }
+    });
 }
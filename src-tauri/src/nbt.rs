@@ -0,0 +1,340 @@
+//! Minimal uncompressed NBT, enough to round-trip `servers.dat`.
+//!
+//! Strings use Java's modified UTF-8, as the game reads and writes them with
+//! `DataInput::readUTF`: NUL is `C0 80`, and characters outside the BMP are
+//! stored as two 3-byte surrogates instead of one 4-byte sequence.
+
+use std::io::{self, Read, Write};
+
+/// Deeper nesting than this is treated as a corrupt file.
+const MAX_DEPTH: usize = 512;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+  Byte(i8),
+  Short(i16),
+  Int(i32),
+  Long(i64),
+  Float(f32),
+  Double(f64),
+  ByteArray(Vec<i8>),
+  String(String),
+  /// Element type id and elements; the id is kept so empty lists round-trip.
+  List(u8, Vec<Tag>),
+  /// Entries in file order.
+  Compound(Vec<(String, Tag)>),
+  IntArray(Vec<i32>),
+  LongArray(Vec<i64>),
+}
+
+const END: u8 = 0;
+
+impl Tag {
+  pub fn id(&self) -> u8 {
+    match self {
+      Tag::Byte(_) => 1,
+      Tag::Short(_) => 2,
+      Tag::Int(_) => 3,
+      Tag::Long(_) => 4,
+      Tag::Float(_) => 5,
+      Tag::Double(_) => 6,
+      Tag::ByteArray(_) => 7,
+      Tag::String(_) => 8,
+      Tag::List(..) => 9,
+      Tag::Compound(_) => 10,
+      Tag::IntArray(_) => 11,
+      Tag::LongArray(_) => 12,
+    }
+  }
+
+  pub fn get(&self, name: &str) -> Option<&Tag> {
+    match self {
+      Tag::Compound(entries) => entries.iter().find(|(n, _)| n == name).map(|(_, t)| t),
+      _ => None,
+    }
+  }
+
+  pub fn get_mut(&mut self, name: &str) -> Option<&mut Tag> {
+    match self {
+      Tag::Compound(entries) => entries.iter_mut().find(|(n, _)| n == name).map(|(_, t)| t),
+      _ => None,
+    }
+  }
+
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Tag::String(s) => Some(s),
+      _ => None,
+    }
+  }
+}
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+fn read_array<const N: usize>(r: &mut impl Read) -> io::Result<[u8; N]> {
+  let mut buf = [0; N];
+  r.read_exact(&mut buf)?;
+  Ok(buf)
+}
+
+fn read_len(r: &mut impl Read) -> io::Result<usize> {
+  let len = i32::from_be_bytes(read_array(r)?);
+  usize::try_from(len).map_err(|_| invalid(format!("negative length {len}")))
+}
+
+fn read_string(r: &mut impl Read) -> io::Result<String> {
+  let len = u16::from_be_bytes(read_array(r)?) as usize;
+  let mut buf = vec![0; len];
+  r.read_exact(&mut buf)?;
+  decode_modified_utf8(&buf)
+}
+
+fn decode_modified_utf8(bytes: &[u8]) -> io::Result<String> {
+  let malformed = || invalid("malformed modified UTF-8 string");
+  let continuation = |b: Option<&u8>| match b {
+    Some(b) if b & 0xC0 == 0x80 => Ok(u16::from(b & 0x3F)),
+    _ => Err(malformed()),
+  };
+
+  let mut units = Vec::with_capacity(bytes.len());
+  let mut iter = bytes.iter();
+  while let Some(&b) = iter.next() {
+    let unit = match b {
+      0x00..=0x7F => u16::from(b),
+      0xC0..=0xDF => u16::from(b & 0x1F) << 6 | continuation(iter.next())?,
+      0xE0..=0xEF => {
+        u16::from(b & 0x0F) << 12 | continuation(iter.next())? << 6 | continuation(iter.next())?
+      }
+      _ => return Err(malformed()),
+    };
+    units.push(unit);
+  }
+  String::from_utf16(&units).map_err(|_| malformed())
+}
+
+fn read_payload(r: &mut impl Read, id: u8, depth: usize) -> io::Result<Tag> {
+  if depth > MAX_DEPTH {
+    return Err(invalid("nesting too deep"));
+  }
+  Ok(match id {
+    1 => Tag::Byte(i8::from_be_bytes(read_array(r)?)),
+    2 => Tag::Short(i16::from_be_bytes(read_array(r)?)),
+    3 => Tag::Int(i32::from_be_bytes(read_array(r)?)),
+    4 => Tag::Long(i64::from_be_bytes(read_array(r)?)),
+    5 => Tag::Float(f32::from_be_bytes(read_array(r)?)),
+    6 => Tag::Double(f64::from_be_bytes(read_array(r)?)),
+    7 => {
+      // Read through `take` so a corrupt length cannot force a huge allocation.
+      let len = read_len(r)?;
+      let mut buf = Vec::new();
+      r.take(len as u64).read_to_end(&mut buf)?;
+      if buf.len() != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+      }
+      Tag::ByteArray(buf.into_iter().map(|b| b as i8).collect())
+    }
+    8 => Tag::String(read_string(r)?),
+    9 => {
+      let element = read_array::<1>(r)?[0];
+      let len = read_len(r)?;
+      let mut items = Vec::new();
+      for _ in 0..len {
+        items.push(read_payload(r, element, depth + 1)?);
+      }
+      Tag::List(element, items)
+    }
+    10 => {
+      let mut entries = Vec::new();
+      loop {
+        let id = read_array::<1>(r)?[0];
+        if id == END {
+          break;
+        }
+        let name = read_string(r)?;
+        entries.push((name, read_payload(r, id, depth + 1)?));
+      }
+      Tag::Compound(entries)
+    }
+    11 => {
+      let len = read_len(r)?;
+      let mut items = Vec::new();
+      for _ in 0..len {
+        items.push(i32::from_be_bytes(read_array(r)?));
+      }
+      Tag::IntArray(items)
+    }
+    12 => {
+      let len = read_len(r)?;
+      let mut items = Vec::new();
+      for _ in 0..len {
+        items.push(i64::from_be_bytes(read_array(r)?));
+      }
+      Tag::LongArray(items)
+    }
+    id => return Err(invalid(format!("unknown tag id {id}"))),
+  })
+}
+
+/// Reads a named root tag, returning its name and value.
+pub fn read(r: &mut impl Read) -> io::Result<(String, Tag)> {
+  let id = read_array::<1>(r)?[0];
+  if id == END {
+    return Err(invalid("empty root tag"));
+  }
+  let name = read_string(r)?;
+  Ok((name, read_payload(r, id, 0)?))
+}
+
+fn write_string(w: &mut impl Write, s: &str) -> io::Result<()> {
+  let bytes = encode_modified_utf8(s);
+  let len = u16::try_from(bytes.len()).map_err(|_| invalid("string longer than 65535 bytes"))?;
+  w.write_all(&len.to_be_bytes())?;
+  w.write_all(&bytes)
+}
+
+fn encode_modified_utf8(s: &str) -> Vec<u8> {
+  let mut bytes = Vec::with_capacity(s.len());
+  for unit in s.encode_utf16() {
+    match unit {
+      0x01..=0x7F => bytes.push(unit as u8),
+      0x00 | 0x80..=0x7FF => bytes.extend([0xC0 | (unit >> 6) as u8, 0x80 | (unit & 0x3F) as u8]),
+      _ => bytes.extend([
+        0xE0 | (unit >> 12) as u8,
+        0x80 | (unit >> 6 & 0x3F) as u8,
+        0x80 | (unit & 0x3F) as u8,
+      ]),
+    }
+  }
+  bytes
+}
+
+fn write_len(w: &mut impl Write, len: usize) -> io::Result<()> {
+  let len = i32::try_from(len).map_err(|_| invalid("array too long"))?;
+  w.write_all(&len.to_be_bytes())
+}
+
+fn write_payload(w: &mut impl Write, tag: &Tag) -> io::Result<()> {
+  match tag {
+    Tag::Byte(v) => w.write_all(&v.to_be_bytes()),
+    Tag::Short(v) => w.write_all(&v.to_be_bytes()),
+    Tag::Int(v) => w.write_all(&v.to_be_bytes()),
+    Tag::Long(v) => w.write_all(&v.to_be_bytes()),
+    Tag::Float(v) => w.write_all(&v.to_be_bytes()),
+    Tag::Double(v) => w.write_all(&v.to_be_bytes()),
+    Tag::ByteArray(items) => {
+      write_len(w, items.len())?;
+      let bytes: Vec<u8> = items.iter().map(|b| *b as u8).collect();
+      w.write_all(&bytes)
+    }
+    Tag::String(s) => write_string(w, s),
+    Tag::List(element, items) => {
+      if let Some(item) = items.iter().find(|t| t.id() != *element) {
+        return Err(invalid(format!(
+          "list of type {element} contains a tag of type {}",
+          item.id()
+        )));
+      }
+      w.write_all(&[*element])?;
+      write_len(w, items.len())?;
+      items.iter().try_for_each(|item| write_payload(w, item))
+    }
+    Tag::Compound(entries) => {
+      for (name, tag) in entries {
+        w.write_all(&[tag.id()])?;
+        write_string(w, name)?;
+        write_payload(w, tag)?;
+      }
+      w.write_all(&[END])
+    }
+    Tag::IntArray(items) => {
+      write_len(w, items.len())?;
+      items.iter().try_for_each(|v| w.write_all(&v.to_be_bytes()))
+    }
+    Tag::LongArray(items) => {
+      write_len(w, items.len())?;
+      items.iter().try_for_each(|v| w.write_all(&v.to_be_bytes()))
+    }
+  }
+}
+
+/// Writes a named root tag.
+pub fn write(w: &mut impl Write, name: &str, tag: &Tag) -> io::Result<()> {
+  w.write_all(&[tag.id()])?;
+  write_string(w, name)?;
+  write_payload(w, tag)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn round_trip(tag: &Tag) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write(&mut bytes, "", tag).unwrap();
+    assert_eq!(
+      read(&mut bytes.as_slice()).unwrap(),
+      (String::new(), tag.clone())
+    );
+    bytes
+  }
+
+  #[test]
+  fn round_trips_every_tag_type() {
+    round_trip(&Tag::Compound(vec![
+      ("byte".into(), Tag::Byte(-1)),
+      ("short".into(), Tag::Short(-2)),
+      ("int".into(), Tag::Int(3)),
+      ("long".into(), Tag::Long(i64::MIN)),
+      ("float".into(), Tag::Float(1.5)),
+      ("double".into(), Tag::Double(-0.25)),
+      ("bytes".into(), Tag::ByteArray(vec![-128, 0, 127])),
+      ("string".into(), Tag::String("mc.example.org".into())),
+      ("ints".into(), Tag::IntArray(vec![1, -1])),
+      ("longs".into(), Tag::LongArray(vec![i64::MAX])),
+      (
+        "nested".into(),
+        Tag::List(10, vec![Tag::Compound(vec![("a".into(), Tag::Byte(1))])]),
+      ),
+    ]));
+  }
+
+  #[test]
+  fn keeps_end_type_of_empty_list() {
+    // The game writes an empty `servers` list with element type TAG_End.
+    let bytes = round_trip(&Tag::Compound(vec![(
+      "servers".into(),
+      Tag::List(END, vec![]),
+    )]));
+    assert_eq!(
+      bytes,
+      [10, 0, 0, 9, 0, 7, b's', b'e', b'r', b'v', b'e', b'r', b's', 0, 0, 0, 0, 0, 0]
+    );
+  }
+
+  #[test]
+  fn strings_use_modified_utf8() {
+    let bytes = round_trip(&Tag::String("\u{1F3AE}\0é".into()));
+    // U+1F3AE as a surrogate pair, NUL as C0 80, then é as plain UTF-8.
+    assert_eq!(
+      bytes[3..],
+      [0, 10, 0xED, 0xA0, 0xBC, 0xED, 0xBE, 0xAE, 0xC0, 0x80, 0xC3, 0xA9]
+    );
+  }
+
+  #[test]
+  fn rejects_standard_utf8_supplementary_characters() {
+    let mut bytes = vec![8, 0, 0, 0, 4];
+    bytes.extend("\u{1F3AE}".as_bytes());
+    assert!(read(&mut bytes.as_slice()).is_err());
+  }
+
+  #[test]
+  fn rejects_truncated_input() {
+    let mut bytes = Vec::new();
+    write(&mut bytes, "", &Tag::ByteArray(vec![1, 2, 3])).unwrap();
+    bytes.pop();
+    assert!(read(&mut bytes.as_slice()).is_err());
+  }
+}
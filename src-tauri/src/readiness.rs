@@ -0,0 +1,340 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cache;
+use crate::error::{LauncherError, Result};
+use crate::versions;
+
+/// Guards against `inheritsFrom` cycles in hand-edited version JSONs.
+const MAX_INHERITANCE_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchReadiness {
+  pub ready: bool,
+  /// Paths relative to the minecraft dir, each with the reason it failed.
+  pub missing: Vec<String>,
+}
+
+fn os_name() -> &'static str {
+  match std::env::consts::OS {
+    "macos" => "osx",
+    os => os,
+  }
+}
+
+fn os_arch() -> &'static str {
+  if cfg!(target_pointer_width = "64") {
+    "64"
+  } else {
+    "32"
+  }
+}
+
+/// Mojang's library rules: the last matching rule wins, and a library with
+/// rules is excluded unless one of them allows it. Rules gated on launcher
+/// features never match here.
+fn rules_allow(rules: &Value) -> bool {
+  let Some(rules) = rules.as_array() else {
+    return true;
+  };
+  let mut allowed = false;
+  for rule in rules {
+    if !rule["features"].is_null() {
+      continue;
+    }
+    let os = &rule["os"];
+    let name_matches = os["name"].as_str().map_or(true, |name| name == os_name());
+    let arch_matches = os["arch"]
+      .as_str()
+      .map_or(true, |arch| arch == std::env::consts::ARCH);
+    if name_matches && arch_matches {
+      allowed = rule["action"].as_str() == Some("allow");
+    }
+  }
+  allowed
+}
+
+/// Library files this platform needs, with their declared size when known.
+fn platform_library_files(json: &Value) -> Vec<(PathBuf, Option<u64>)> {
+  let Some(libraries) = json["libraries"].as_array() else {
+    return Vec::new();
+  };
+
+  let mut files = Vec::new();
+  for library in libraries.iter().filter(|l| rules_allow(&l["rules"])) {
+    let downloads = &library["downloads"];
+    let name = library["name"].as_str();
+    let native = library["natives"][os_name()]
+      .as_str()
+      .map(|c| c.replace("${arch}", os_arch()));
+
+    if let Some(path) = downloads["artifact"]["path"].as_str() {
+      files.push((PathBuf::from(path), downloads["artifact"]["size"].as_u64()));
+    } else if downloads["classifiers"].is_null() && native.is_none() {
+      if let Some(path) = name.and_then(|n| versions::maven_path(n, None)) {
+        files.push((path, None));
+      }
+    }
+
+    if let Some(classifier) = native {
+      let download = &downloads["classifiers"][classifier.as_str()];
+      match download["path"].as_str() {
+        Some(path) => files.push((PathBuf::from(path), download["size"].as_u64())),
+        None => {
+          if let Some(path) = name.and_then(|n| versions::maven_path(n, Some(&classifier))) {
+            files.push((path, None));
+          }
+        }
+      }
+    }
+  }
+  files
+}
+
+struct Check<'a> {
+  minecraft_dir: &'a Path,
+  missing: Vec<String>,
+}
+
+impl Check<'_> {
+  fn file(&mut self, path: &Path, size: Option<u64>) {
+    let relative = path
+      .strip_prefix(self.minecraft_dir)
+      .unwrap_or(path)
+      .to_string_lossy()
+      .replace('\\', "/");
+    match fs::metadata(path) {
+      Ok(meta) if meta.is_file() => {
+        if size.is_some_and(|size| size != meta.len()) {
+          self.missing.push(format!("{relative} (size mismatch)"));
+        }
+      }
+      _ => self.missing.push(relative),
+    }
+  }
+}
+
+/// The version and its ancestors, child first.
+fn resolve_chain(check: &mut Check, version_id: &str) -> Result<Vec<(String, Value)>> {
+  let mut chain: Vec<(String, Value)> = Vec::new();
+  let mut next = Some(version_id.to_string());
+  while let Some(id) = next.take() {
+    if chain.len() >= MAX_INHERITANCE_DEPTH || chain.iter().any(|(seen, _)| *seen == id) {
+      check
+        .missing
+        .push(format!("versions/{id}/{id}.json (inheritance cycle)"));
+      break;
+    }
+    versions::validate_id(&id)?;
+    let path = versions::version_json_path(check.minecraft_dir, &id);
+    if !path.is_file() {
+      check.file(&path, None);
+      break;
+    }
+    let json = match versions::read_version_json(check.minecraft_dir, &id) {
+      Ok(json) => json,
+      Err(LauncherError::MalformedJson { .. }) => {
+        check
+          .missing
+          .push(format!("versions/{id}/{id}.json (malformed)"));
+        break;
+      }
+      Err(e) => return Err(e),
+    };
+    next = versions::inherits_from(&json).map(str::to_string);
+    chain.push((id, json));
+  }
+  Ok(chain)
+}
+
+fn can_launch_offline_blocking(minecraft_dir: &Path, version_id: &str) -> Result<LaunchReadiness> {
+  versions::validate_id(version_id)?;
+  if !versions::version_json_path(minecraft_dir, version_id).is_file() {
+    return Err(LauncherError::VersionNotInstalled(version_id.to_string()));
+  }
+
+  let mut check = Check {
+    minecraft_dir,
+    missing: Vec::new(),
+  };
+  let chain = resolve_chain(&mut check, version_id)?;
+
+  // A `jar` field names the version whose jar to run. Without one, loader
+  // versions usually run on the first ancestor that downloads a client.
+  let jar_id = chain
+    .iter()
+    .find_map(|(_, json)| json["jar"].as_str())
+    .or_else(|| {
+      chain
+        .iter()
+        .find(|(_, json)| json["downloads"]["client"].is_object())
+        .map(|(id, _)| id.as_str())
+    })
+    .unwrap_or(version_id);
+  versions::validate_id(jar_id)?;
+  let size = chain
+    .iter()
+    .find(|(id, _)| id == jar_id)
+    .and_then(|(_, json)| json["downloads"]["client"]["size"].as_u64());
+  let jar = versions::version_dir(minecraft_dir, jar_id).join(format!("{jar_id}.jar"));
+  check.file(&jar, size);
+
+  let libraries = cache::libraries_dir(minecraft_dir);
+  for (_, json) in &chain {
+    for (path, size) in platform_library_files(json) {
+      check.file(&libraries.join(path), size);
+    }
+  }
+
+  if let Some(index) = chain
+    .iter()
+    .find_map(|(_, json)| versions::asset_index_id(json))
+  {
+    let index_path = cache::indexes_dir(minecraft_dir).join(format!("{index}.json"));
+    if !index_path.is_file() {
+      check.file(&index_path, None);
+    } else {
+      match cache::read_index_objects(minecraft_dir, index) {
+        Ok(objects) => {
+          for (hash, size) in objects {
+            check.file(&cache::object_path(minecraft_dir, &hash), size);
+          }
+        }
+        Err(e) => {
+          log::warn!("cannot read asset index {index}: {e}");
+          check
+            .missing
+            .push(format!("assets/indexes/{index}.json (malformed)"));
+        }
+      }
+    }
+  }
+
+  check.missing.sort();
+  check.missing.dedup();
+  Ok(LaunchReadiness {
+    ready: check.missing.is_empty(),
+    missing: check.missing,
+  })
+}
+
+/// Checks that a version's JSONs, jar, libraries and assets are all on disk,
+/// comparing sizes but not hashes, so it is fast enough to run before every
+/// offline launch.
+#[tauri::command]
+pub async fn can_launch_offline(
+  minecraft_dir: String,
+  version_id: String,
+) -> Result<LaunchReadiness> {
+  tauri::async_runtime::spawn_blocking(move || {
+    can_launch_offline_blocking(Path::new(&minecraft_dir), &version_id)
+  })
+  .await?
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::versions::tests::install;
+  use serde_json::json;
+
+  #[test]
+  fn reports_missing_jar_and_library() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(
+      mc,
+      "1.20.1",
+      json!({
+        "downloads": { "client": { "size": 3 } },
+        "libraries": [{ "downloads": { "artifact": { "path": "com/example/example.jar" } } }],
+      }),
+    );
+
+    let readiness = can_launch_offline_blocking(mc, "1.20.1").unwrap();
+
+    assert!(!readiness.ready);
+    assert_eq!(
+      readiness.missing,
+      [
+        "libraries/com/example/example.jar",
+        "versions/1.20.1/1.20.1.jar"
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_malformed_parent_json() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(
+      mc,
+      "fabric",
+      json!({ "inheritsFrom": "1.20.1", "jar": "1.20.1" }),
+    );
+    fs::create_dir_all(versions::version_dir(mc, "1.20.1")).unwrap();
+    fs::write(versions::version_json_path(mc, "1.20.1"), "{").unwrap();
+    fs::write(
+      versions::version_dir(mc, "1.20.1").join("1.20.1.jar"),
+      b"jar",
+    )
+    .unwrap();
+
+    let readiness = can_launch_offline_blocking(mc, "fabric").unwrap();
+
+    assert_eq!(
+      readiness.missing,
+      ["versions/1.20.1/1.20.1.json (malformed)"]
+    );
+  }
+
+  #[test]
+  fn checks_the_jar_named_by_the_jar_field() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(
+      mc,
+      "1.20.1",
+      json!({ "downloads": { "client": { "size": 3 } } }),
+    );
+    fs::write(
+      versions::version_dir(mc, "1.20.1").join("1.20.1.jar"),
+      b"jar",
+    )
+    .unwrap();
+    install(
+      mc,
+      "patched",
+      json!({ "inheritsFrom": "1.20.1", "jar": "patched-client" }),
+    );
+
+    let readiness = can_launch_offline_blocking(mc, "patched").unwrap();
+
+    assert_eq!(
+      readiness.missing,
+      ["versions/patched-client/patched-client.jar"]
+    );
+  }
+
+  #[test]
+  fn reports_malformed_asset_index() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(mc, "1.20.1", json!({ "assetIndex": { "id": "5" } }));
+    fs::write(
+      versions::version_dir(mc, "1.20.1").join("1.20.1.jar"),
+      b"jar",
+    )
+    .unwrap();
+    fs::create_dir_all(cache::indexes_dir(mc)).unwrap();
+    fs::write(cache::indexes_dir(mc).join("5.json"), "{").unwrap();
+
+    let readiness = can_launch_offline_blocking(mc, "1.20.1").unwrap();
+
+    assert_eq!(readiness.missing, ["assets/indexes/5.json (malformed)"]);
+  }
+}
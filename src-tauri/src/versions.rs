@@ -0,0 +1,770 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cache;
+use crate::error::{LauncherError, Result};
+
+pub fn versions_dir(minecraft_dir: &Path) -> PathBuf {
+  minecraft_dir.join("versions")
+}
+
+pub fn version_dir(minecraft_dir: &Path, id: &str) -> PathBuf {
+  versions_dir(minecraft_dir).join(id)
+}
+
+pub fn version_json_path(minecraft_dir: &Path, id: &str) -> PathBuf {
+  version_dir(minecraft_dir, id).join(format!("{id}.json"))
+}
+
+/// Rejects ids that would resolve outside `versions/` once joined.
+pub fn validate_id(id: &str) -> Result<()> {
+  let invalid =
+    id.is_empty() || id == "." || id == ".." || id.contains(['/', '\\', ':']) || id.trim() != id;
+  if invalid {
+    return Err(LauncherError::InvalidVersionId(id.to_string()));
+  }
+  Ok(())
+}
+
+pub fn read_version_json(minecraft_dir: &Path, id: &str) -> Result<Value> {
+  let path = version_json_path(minecraft_dir, id);
+  let text = fs::read_to_string(&path)?;
+  serde_json::from_str(&text).map_err(|source| LauncherError::MalformedJson { path, source })
+}
+
+/// Ids of every `versions/<id>/` that contains a `<id>.json`, plus the names
+/// of version folders that are not valid UTF-8 and so have no usable id.
+fn scan_installed(minecraft_dir: &Path) -> Result<(Vec<String>, Vec<OsString>)> {
+  let dir = versions_dir(minecraft_dir);
+  if !dir.is_dir() {
+    return Ok(Default::default());
+  }
+
+  let (mut ids, mut invalid) = (Vec::new(), Vec::new());
+  for entry in fs::read_dir(dir)? {
+    let entry = entry?;
+    if !entry.file_type()?.is_dir() {
+      continue;
+    }
+    let name = entry.file_name();
+    match name.to_str() {
+      Some(id) if version_json_path(minecraft_dir, id).is_file() => ids.push(id.to_string()),
+      Some(_) => {}
+      None => {
+        let mut json = name.clone();
+        json.push(".json");
+        if entry.path().join(json).is_file() {
+          invalid.push(name);
+        }
+      }
+    }
+  }
+  ids.sort();
+  Ok((ids, invalid))
+}
+
+/// Ids of every `versions/<id>/` that contains a `<id>.json`.
+///
+/// Fails on a version folder whose name is not valid UTF-8: skipping it
+/// would hide what it references or inherits from, which garbage collection
+/// and the parent checks rely on.
+pub fn installed_ids(minecraft_dir: &Path) -> Result<Vec<String>> {
+  let (ids, invalid) = scan_installed(minecraft_dir)?;
+  match invalid.first() {
+    Some(name) => Err(LauncherError::InvalidVersionId(
+      name.to_string_lossy().into_owned(),
+    )),
+    None => Ok(ids),
+  }
+}
+
+pub fn inherits_from(json: &Value) -> Option<&str> {
+  json["inheritsFrom"].as_str()
+}
+
+/// Asset index id, falling back to the legacy top-level `assets` field.
+pub fn asset_index_id(json: &Value) -> Option<&str> {
+  json["assetIndex"]["id"]
+    .as_str()
+    .or_else(|| json["assets"].as_str())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LibraryFile {
+  /// Relative to `libraries/`.
+  pub path: PathBuf,
+  pub sha1: Option<String>,
+}
+
+impl LibraryFile {
+  fn from_download(download: &Value) -> Option<Self> {
+    Some(Self {
+      path: PathBuf::from(download["path"].as_str()?),
+      sha1: download["sha1"].as_str().map(str::to_string),
+    })
+  }
+
+  fn from_maven(name: &str, classifier: Option<&str>) -> Option<Self> {
+    Some(Self {
+      path: maven_path(name, classifier)?,
+      sha1: None,
+    })
+  }
+}
+
+/// Every artifact and native classifier a version lists. Natives for all
+/// platforms are included on purpose.
+pub fn library_files(json: &Value) -> Vec<LibraryFile> {
+  let Some(libraries) = json["libraries"].as_array() else {
+    return Vec::new();
+  };
+
+  let mut files = Vec::new();
+  for library in libraries {
+    let downloads = &library["downloads"];
+    match LibraryFile::from_download(&downloads["artifact"]) {
+      Some(file) => files.push(file),
+      // Fabric/Quilt style entries only carry a maven name and repo url.
+      None if downloads["classifiers"].is_null() => {
+        if let Some(file) = library["name"]
+          .as_str()
+          .and_then(|n| LibraryFile::from_maven(n, None))
+        {
+          files.push(file);
+        }
+      }
+      None => {}
+    }
+
+    if let Some(classifiers) = downloads["classifiers"].as_object() {
+      files.extend(classifiers.values().filter_map(LibraryFile::from_download));
+    } else if let (Some(name), Some(natives)) =
+      (library["name"].as_str(), library["natives"].as_object())
+    {
+      for classifier in natives.values().filter_map(Value::as_str) {
+        for arch in ["32", "64"] {
+          let classifier = classifier.replace("${arch}", arch);
+          if let Some(file) = LibraryFile::from_maven(name, Some(&classifier)) {
+            files.push(file);
+          }
+        }
+      }
+    }
+  }
+  files.sort();
+  files.dedup();
+  files
+}
+
+/// `group:artifact:version[:classifier][@ext]` -> `group/artifact/version/artifact-version[-classifier].ext`
+pub fn maven_path(name: &str, classifier: Option<&str>) -> Option<PathBuf> {
+  let (coords, ext) = name.split_once('@').unwrap_or((name, "jar"));
+  let mut parts = coords.split(':');
+  let group = parts.next()?;
+  let artifact = parts.next()?;
+  let version = parts.next()?;
+  let classifier = classifier.or(parts.next());
+
+  let file = match classifier {
+    Some(c) => format!("{artifact}-{version}-{c}.{ext}"),
+    None => format!("{artifact}-{version}.{ext}"),
+  };
+
+  let mut path: PathBuf = group.split('.').collect();
+  path.push(artifact);
+  path.push(version);
+  path.push(file);
+  Some(path)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LoaderKind {
+  Forge,
+  NeoForge,
+  Fabric,
+  Quilt,
+  OptiFine,
+  LiteLoader,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderInfo {
+  pub kind: LoaderKind,
+  pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledVersion {
+  pub id: String,
+  #[serde(rename = "type")]
+  pub kind: Option<String>,
+  pub release_time: Option<String>,
+  pub loader: Option<LoaderInfo>,
+  pub inherits_from: Option<String>,
+  pub last_played: Option<String>,
+}
+
+/// Library name prefixes that identify a mod loader, checked in order.
+const LOADER_LIBRARIES: &[(&str, LoaderKind)] = &[
+  ("net.neoforged:neoforge:", LoaderKind::NeoForge),
+  ("net.neoforged:forge:", LoaderKind::NeoForge),
+  ("net.minecraftforge:forge:", LoaderKind::Forge),
+  ("net.minecraftforge:fmlloader:", LoaderKind::Forge),
+  ("net.fabricmc:fabric-loader:", LoaderKind::Fabric),
+  ("org.quiltmc:quilt-loader:", LoaderKind::Quilt),
+  ("optifine:OptiFine:", LoaderKind::OptiFine),
+  ("com.mumfrey:liteloader:", LoaderKind::LiteLoader),
+];
+
+pub fn detect_loader(json: &Value) -> Option<LoaderInfo> {
+  let names: Vec<&str> = json["libraries"]
+    .as_array()?
+    .iter()
+    .filter_map(|l| l["name"].as_str())
+    .collect();
+
+  LOADER_LIBRARIES.iter().find_map(|(prefix, kind)| {
+    let name = names.iter().find(|n| n.starts_with(prefix))?;
+    let version = name[prefix.len()..].split(':').next().unwrap_or_default();
+    // Forge (and early NeoForge) versions are written as `<minecraft>-<forge>`.
+    let version = if prefix.ends_with(":forge:") || prefix.ends_with(":fmlloader:") {
+      version.split_once('-').map_or(version, |(_, v)| v)
+    } else {
+      version
+    };
+    Some(LoaderInfo {
+      kind: *kind,
+      version: (!version.is_empty()).then(|| version.to_string()),
+    })
+  })
+}
+
+fn list_installed_versions_blocking(minecraft_dir: &Path) -> Result<Vec<InstalledVersion>> {
+  let (ids, invalid) = scan_installed(minecraft_dir)?;
+  for name in invalid {
+    log::warn!("skipping version folder {name:?}: name is not valid UTF-8");
+  }
+  let mut versions = Vec::new();
+  for id in ids {
+    let json = match read_version_json(minecraft_dir, &id) {
+      Ok(json) if json.is_object() => json,
+      Ok(_) => {
+        log::warn!("skipping version {id}: version JSON is not an object");
+        continue;
+      }
+      Err(e) => {
+        log::warn!("skipping version {id}: {e}");
+        continue;
+      }
+    };
+    versions.push(InstalledVersion {
+      kind: json["type"].as_str().map(str::to_string),
+      release_time: json["releaseTime"].as_str().map(str::to_string),
+      loader: detect_loader(&json),
+      inherits_from: inherits_from(&json).map(str::to_string),
+      last_played: None,
+      id,
+    });
+  }
+
+  // Children often leave out fields they inherit from their parent.
+  for i in 0..versions.len() {
+    let Some(parent) = versions[i].inherits_from.clone() else {
+      continue;
+    };
+    let Some(parent) = versions.iter().find(|v| v.id == parent).cloned() else {
+      continue;
+    };
+    let version = &mut versions[i];
+    version.kind = version.kind.take().or(parent.kind);
+    version.release_time = version.release_time.take().or(parent.release_time);
+  }
+
+  // Most recently played first, then newest release; `None` sorts last.
+  versions.sort_by(|a, b| {
+    b.last_played
+      .cmp(&a.last_played)
+      .then_with(|| b.release_time.cmp(&a.release_time))
+      .then_with(|| a.id.cmp(&b.id))
+  });
+  Ok(versions)
+}
+
+/// Every version under `versions/`. Malformed version JSONs are skipped with
+/// a warning rather than failing the whole scan.
+#[tauri::command]
+pub async fn list_installed_versions(minecraft_dir: String) -> Result<Vec<InstalledVersion>> {
+  tauri::async_runtime::spawn_blocking(move || {
+    list_installed_versions_blocking(Path::new(&minecraft_dir))
+  })
+  .await?
+}
+
+pub(crate) fn dir_size(path: &Path) -> Result<u64> {
+  let mut total = 0;
+  cache::walk_files(path, &mut |_, meta| total += meta.len())?;
+  Ok(total)
+}
+
+fn delete_version_blocking(
+  minecraft_dir: &Path,
+  version_id: &str,
+  collect_garbage: bool,
+) -> Result<u64> {
+  validate_id(version_id)?;
+  let dir = version_dir(minecraft_dir, version_id);
+  if !dir.is_dir() {
+    return Err(LauncherError::VersionNotInstalled(version_id.to_string()));
+  }
+
+  // Deleting a parent would leave its children unlaunchable.
+  if let Some((child, _)) = children_of(minecraft_dir, version_id)?.into_iter().next() {
+    return Err(LauncherError::VersionInUse {
+      version: version_id.to_string(),
+      child,
+    });
+  }
+
+  let mut freed = dir_size(&dir)?;
+  fs::remove_dir_all(&dir)?;
+  log::info!("deleted version {version_id}, freed {freed} bytes");
+
+  // The version is already gone at this point, so a failed collection only
+  // means less space reclaimed.
+  if collect_garbage {
+    match cache::collect_garbage(minecraft_dir) {
+      Ok(report) => freed += report.freed_bytes,
+      Err(e) => log::warn!("skipped garbage collection: {e}"),
+    }
+  }
+  Ok(freed)
+}
+
+/// Removes `versions/<id>/` and, when `collect_garbage` is set, any shared
+/// libraries and asset objects no remaining version references. Returns the
+/// number of bytes freed.
+#[tauri::command]
+pub async fn delete_version(
+  minecraft_dir: String,
+  version_id: String,
+  collect_garbage: bool,
+) -> Result<u64> {
+  tauri::async_runtime::spawn_blocking(move || {
+    delete_version_blocking(Path::new(&minecraft_dir), &version_id, collect_garbage)
+  })
+  .await?
+}
+
+fn write_version_json(path: &Path, json: &Value) -> Result<()> {
+  fs::write(path, serde_json::to_string_pretty(json)?)?;
+  Ok(())
+}
+
+/// Versions whose `inheritsFrom` is `parent`. Fails if any other version's
+/// JSON cannot be read, since nothing can tell whether it is one of them.
+fn children_of(minecraft_dir: &Path, parent: &str) -> Result<Vec<(String, Value)>> {
+  let mut children = Vec::new();
+  for id in installed_ids(minecraft_dir)? {
+    if id == parent {
+      continue;
+    }
+    let json = read_version_json(minecraft_dir, &id)?;
+    if inherits_from(&json) == Some(parent) {
+      children.push((id, json));
+    }
+  }
+  Ok(children)
+}
+
+/// Renames the files inside an already moved version folder. On failure the
+/// files that were moved are put back so the folder is left as it was.
+fn rename_version_files(dir: &Path, old_id: &str, new_id: &str, json: &Value) -> Result<()> {
+  let old_json = dir.join(format!("{old_id}.json"));
+  let new_json = dir.join(format!("{new_id}.json"));
+  let renames = [
+    (format!("{old_id}.jar"), format!("{new_id}.jar")),
+    (format!("{old_id}-natives"), format!("{new_id}-natives")),
+  ];
+
+  write_version_json(&new_json, json)?;
+  let mut done = Vec::new();
+  let mut result = Ok(());
+  for (from, to) in &renames {
+    let (from, to) = (dir.join(from), dir.join(to));
+    if !from.exists() {
+      continue;
+    }
+    if let Err(e) = fs::rename(&from, &to) {
+      result = Err(e);
+      break;
+    }
+    done.push((from, to));
+  }
+  if result.is_ok() {
+    result = fs::remove_file(&old_json);
+  }
+
+  if let Err(e) = result {
+    for (from, to) in done.iter().rev() {
+      if let Err(e) = fs::rename(to, from) {
+        log::error!("failed to restore {}: {e}", from.display());
+      }
+    }
+    let _ = fs::remove_file(&new_json);
+    return Err(e.into());
+  }
+  Ok(())
+}
+
+/// Points each child at `new_id`. If a write fails, the children already
+/// rewritten get their original JSON back.
+fn repoint_children(
+  minecraft_dir: &Path,
+  children: &[(String, Value)],
+  old_id: &str,
+  new_id: &str,
+) -> Result<()> {
+  for (i, (id, json)) in children.iter().enumerate() {
+    let mut child = json.clone();
+    child["inheritsFrom"] = Value::from(new_id);
+    if child["jar"].as_str() == Some(old_id) {
+      child["jar"] = Value::from(new_id);
+    }
+    if let Err(e) = write_version_json(&version_json_path(minecraft_dir, id), &child) {
+      for (id, json) in &children[..=i] {
+        if let Err(e) = write_version_json(&version_json_path(minecraft_dir, id), json) {
+          log::error!("failed to restore version {id}: {e}");
+        }
+      }
+      return Err(e);
+    }
+  }
+  Ok(())
+}
+
+fn rename_version_blocking(minecraft_dir: &Path, old_id: &str, new_id: &str) -> Result<()> {
+  validate_id(old_id)?;
+  validate_id(new_id)?;
+  if old_id == new_id {
+    return Ok(());
+  }
+  if !version_json_path(minecraft_dir, old_id).is_file() {
+    return Err(LauncherError::VersionNotInstalled(old_id.to_string()));
+  }
+  let old_dir = version_dir(minecraft_dir, old_id);
+  let new_dir = version_dir(minecraft_dir, new_id);
+  if new_dir.exists() {
+    return Err(LauncherError::VersionExists(new_id.to_string()));
+  }
+
+  let old_json = read_version_json(minecraft_dir, old_id)?;
+  let mut json = old_json.clone();
+  json["id"] = Value::from(new_id);
+  if json["jar"].as_str() == Some(old_id) {
+    json["jar"] = Value::from(new_id);
+  }
+  // Children point at their parent by id, both for inheritance and for the
+  // jar. Read them all up front so nothing has moved if one is unreadable.
+  let children = children_of(minecraft_dir, old_id)?;
+
+  // Keep the version launchable under its old name if any step fails.
+  let restore_dir = || {
+    if let Err(e) = fs::rename(&new_dir, &old_dir) {
+      log::error!("failed to restore {}: {e}", old_dir.display());
+    }
+  };
+  fs::rename(&old_dir, &new_dir)?;
+  if let Err(e) = rename_version_files(&new_dir, old_id, new_id, &json) {
+    restore_dir();
+    return Err(e);
+  }
+  if let Err(e) = repoint_children(minecraft_dir, &children, old_id, new_id) {
+    if let Err(e) = rename_version_files(&new_dir, new_id, old_id, &old_json) {
+      log::error!("failed to restore files of version {old_id}: {e}");
+    }
+    restore_dir();
+    return Err(e);
+  }
+
+  log::info!("renamed version {old_id} to {new_id}");
+  Ok(())
+}
+
+/// Renames `versions/<old>/` and the files in it, rewrites the JSON `id`, and
+/// repoints any version that inherits from it.
+#[tauri::command]
+pub async fn rename_version(minecraft_dir: String, old_id: String, new_id: String) -> Result<()> {
+  tauri::async_runtime::spawn_blocking(move || {
+    rename_version_blocking(Path::new(&minecraft_dir), &old_id, &new_id)
+  })
+  .await?
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionSize {
+  /// Size of `versions/<id>/` itself.
+  pub folder_bytes: u64,
+  /// Libraries no other installed version uses.
+  pub library_bytes: u64,
+  /// Asset indexes and objects no other installed version uses.
+  pub asset_bytes: u64,
+  /// What deleting the version and collecting garbage would reclaim.
+  pub total_bytes: u64,
+}
+
+fn file_len(path: &Path) -> u64 {
+  fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn version_size_blocking(minecraft_dir: &Path, version_id: &str) -> Result<VersionSize> {
+  validate_id(version_id)?;
+  let dir = version_dir(minecraft_dir, version_id);
+  if !dir.is_dir() {
+    return Err(LauncherError::VersionNotInstalled(version_id.to_string()));
+  }
+
+  let own = cache::collect_references_for(minecraft_dir, &[version_id.to_string()])?;
+  let others = cache::collect_references(minecraft_dir, Some(version_id))?;
+
+  // Same rules as cache::find_orphans, so the total matches what a delete
+  // with garbage collection frees.
+  let libraries = cache::libraries_dir(minecraft_dir);
+  let library_bytes = own
+    .libraries
+    .keys()
+    .filter(|path| !others.libraries.contains_key(*path) && !cache::is_protected_library(path))
+    .map(|path| file_len(&libraries.join(path)))
+    .sum();
+
+  let indexes = cache::indexes_dir(minecraft_dir);
+  let mut asset_bytes: u64 = own
+    .asset_indexes
+    .difference(&others.asset_indexes)
+    .map(|index| file_len(&indexes.join(format!("{index}.json"))))
+    .sum();
+  // With an incomplete picture of the other versions' objects, claiming any
+  // object as exclusive could overstate what a delete would free.
+  if !others.objects_incomplete {
+    asset_bytes += own
+      .objects
+      .difference(&others.objects)
+      .map(|hash| file_len(&cache::object_path(minecraft_dir, hash)))
+      .sum::<u64>();
+  }
+
+  let folder_bytes = dir_size(&dir)?;
+  Ok(VersionSize {
+    folder_bytes,
+    library_bytes,
+    asset_bytes,
+    total_bytes: folder_bytes + library_bytes + asset_bytes,
+  })
+}
+
+/// Disk usage of a version, counting shared libraries and assets only when no
+/// other installed version uses them.
+#[tauri::command]
+pub async fn version_size(minecraft_dir: String, version_id: String) -> Result<VersionSize> {
+  tauri::async_runtime::spawn_blocking(move || {
+    version_size_blocking(Path::new(&minecraft_dir), &version_id)
+  })
+  .await?
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+  use serde_json::json;
+
+  /// Writes `versions/<id>/<id>.json`. Shared with the other modules' tests.
+  pub(crate) fn install(minecraft_dir: &Path, id: &str, json: Value) {
+    let dir = version_dir(minecraft_dir, id);
+    fs::create_dir_all(&dir).unwrap();
+    write_version_json(&dir.join(format!("{id}.json")), &json).unwrap();
+  }
+
+  #[test]
+  fn delete_refuses_parent_of_another_version() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(mc, "1.20.1", json!({ "id": "1.20.1" }));
+    install(
+      mc,
+      "fabric",
+      json!({ "id": "fabric", "inheritsFrom": "1.20.1" }),
+    );
+
+    let err = delete_version_blocking(mc, "1.20.1", false).unwrap_err();
+    assert!(matches!(err, LauncherError::VersionInUse { child, .. } if child == "fabric"));
+    assert!(version_dir(mc, "1.20.1").is_dir());
+  }
+
+  #[test]
+  fn delete_refuses_when_a_version_json_is_malformed() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(mc, "1.20.1", json!({ "id": "1.20.1" }));
+    fs::create_dir_all(version_dir(mc, "broken")).unwrap();
+    fs::write(version_json_path(mc, "broken"), "{").unwrap();
+
+    let err = delete_version_blocking(mc, "1.20.1", false).unwrap_err();
+    assert!(matches!(err, LauncherError::MalformedJson { .. }));
+    assert!(version_dir(mc, "1.20.1").is_dir());
+  }
+
+  #[test]
+  fn size_counts_only_libraries_a_delete_would_free() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    let library = |path: &str| json!({ "downloads": { "artifact": { "path": path } } });
+    let write = |path: &str, len: usize| {
+      let path = cache::libraries_dir(mc).join(path);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(path, vec![0; len]).unwrap();
+    };
+    install(
+      mc,
+      "1.20.1",
+      json!({ "libraries": [library("com/shared/shared.jar")] }),
+    );
+    install(
+      mc,
+      "forge",
+      json!({
+        "inheritsFrom": "1.20.1",
+        "libraries": [
+          library("com/shared/shared.jar"),
+          library("com/exclusive/exclusive.jar"),
+          library("net/minecraftforge/forge/1.20.1-47.1.0/forge-1.20.1-47.1.0-client.jar"),
+        ],
+      }),
+    );
+    write("com/shared/shared.jar", 20);
+    write("com/exclusive/exclusive.jar", 10);
+    write(
+      "net/minecraftforge/forge/1.20.1-47.1.0/forge-1.20.1-47.1.0-client.jar",
+      1000,
+    );
+
+    let size = version_size_blocking(mc, "forge").unwrap();
+
+    assert_eq!(size.library_bytes, 10);
+    assert_eq!(size.asset_bytes, 0);
+    assert_eq!(size.total_bytes, size.folder_bytes + 10);
+    assert_eq!(
+      delete_version_blocking(mc, "forge", true).unwrap(),
+      size.total_bytes
+    );
+  }
+
+  #[test]
+  fn rename_moves_files_and_repoints_children() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(mc, "1.20.1", json!({ "id": "1.20.1" }));
+    fs::write(version_dir(mc, "1.20.1").join("1.20.1.jar"), b"jar").unwrap();
+    install(
+      mc,
+      "fabric",
+      json!({ "id": "fabric", "inheritsFrom": "1.20.1", "jar": "1.20.1" }),
+    );
+
+    rename_version_blocking(mc, "1.20.1", "vanilla").unwrap();
+
+    assert!(!version_dir(mc, "1.20.1").exists());
+    assert!(version_dir(mc, "vanilla").join("vanilla.jar").is_file());
+    assert_eq!(read_version_json(mc, "vanilla").unwrap()["id"], "vanilla");
+    let child = read_version_json(mc, "fabric").unwrap();
+    assert_eq!(child["inheritsFrom"], "vanilla");
+    assert_eq!(child["jar"], "vanilla");
+  }
+
+  #[test]
+  fn rename_refuses_when_a_version_json_is_malformed() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(mc, "1.20.1", json!({ "id": "1.20.1" }));
+    fs::create_dir_all(version_dir(mc, "broken")).unwrap();
+    fs::write(version_json_path(mc, "broken"), "{").unwrap();
+
+    let err = rename_version_blocking(mc, "1.20.1", "vanilla").unwrap_err();
+    assert!(matches!(err, LauncherError::MalformedJson { .. }));
+    assert!(version_json_path(mc, "1.20.1").is_file());
+    assert!(!version_dir(mc, "vanilla").exists());
+  }
+
+  fn loader(json: Value) -> Option<(LoaderKind, Option<String>)> {
+    detect_loader(&json).map(|info| (info.kind, info.version))
+  }
+
+  #[test]
+  fn detects_loaders_from_libraries() {
+    assert_eq!(
+      loader(json!({ "libraries": [
+        { "name": "org.ow2.asm:asm:9.6" },
+        { "name": "net.minecraftforge:forge:1.20.1-47.1.0:universal" },
+      ] })),
+      Some((LoaderKind::Forge, Some("47.1.0".to_string())))
+    );
+    assert_eq!(
+      loader(json!({ "libraries": [{ "name": "net.neoforged:neoforge:20.4.237" }] })),
+      Some((LoaderKind::NeoForge, Some("20.4.237".to_string())))
+    );
+    assert_eq!(
+      loader(json!({ "libraries": [{ "name": "net.neoforged:forge:1.20.1-47.1.79" }] })),
+      Some((LoaderKind::NeoForge, Some("47.1.79".to_string())))
+    );
+    assert_eq!(
+      loader(json!({ "libraries": [{ "name": "net.fabricmc:fabric-loader:0.15.11" }] })),
+      Some((LoaderKind::Fabric, Some("0.15.11".to_string())))
+    );
+    assert_eq!(
+      loader(json!({ "libraries": [{ "name": "org.ow2.asm:asm:9.6" }] })),
+      None
+    );
+  }
+
+  #[test]
+  fn list_skips_malformed_versions_and_fills_in_inherited_fields() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mc = tmp.path();
+    install(
+      mc,
+      "1.20.1",
+      json!({ "type": "release", "releaseTime": "2023-06-12T13:25:51+00:00" }),
+    );
+    install(
+      mc,
+      "fabric",
+      json!({
+        "inheritsFrom": "1.20.1",
+        "libraries": [{ "name": "net.fabricmc:fabric-loader:0.15.11" }],
+      }),
+    );
+    fs::create_dir_all(version_dir(mc, "broken")).unwrap();
+    fs::write(version_json_path(mc, "broken"), "{").unwrap();
+
+    let versions = list_installed_versions_blocking(mc).unwrap();
+
+    let ids: Vec<&str> = versions.iter().map(|v| v.id.as_str()).collect();
+    assert_eq!(ids, ["1.20.1", "fabric"]);
+    let fabric = &versions[1];
+    assert_eq!(fabric.kind.as_deref(), Some("release"));
+    assert_eq!(
+      fabric.release_time.as_deref(),
+      Some("2023-06-12T13:25:51+00:00")
+    );
+    assert_eq!(fabric.inherits_from.as_deref(), Some("1.20.1"));
+    assert_eq!(
+      fabric.loader.as_ref().map(|l| l.kind),
+      Some(LoaderKind::Fabric)
+    );
+  }
+}
@@ -0,0 +1,212 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+  AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime, Webview, WebviewWindow, Window,
+};
+
+use crate::error::Result;
+
+pub const MAIN_WINDOW: &str = "main";
+
+const STATE_FILE: &str = "window-state.json";
+
+/// Smallest size we restore to; anything below that is treated as bogus.
+const MIN_WIDTH: u32 = 640;
+const MIN_HEIGHT: u32 = 480;
+
+/// Geometry in physical pixels. The window has no native decorations on any
+/// platform (the title bar is drawn by the frontend), so the outer position
+/// and the inner size describe the same rectangle everywhere.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct WindowState {
+  x: i32,
+  y: i32,
+  width: u32,
+  height: u32,
+  maximized: bool,
+}
+
+fn state_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+  app
+    .path()
+    .app_config_dir()
+    .ok()
+    .map(|dir| dir.join(STATE_FILE))
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>) -> Option<WindowState> {
+  let text = fs::read_to_string(state_path(app)?).ok()?;
+  serde_json::from_str(&text).ok()
+}
+
+fn try_save<R: Runtime>(window: &Window<R>) -> Result<()> {
+  // A minimized window reports a meaningless position (-32000 on Windows).
+  if window.is_minimized()? {
+    return Ok(());
+  }
+  let app = window.app_handle();
+  let Some(path) = state_path(app) else {
+    return Ok(());
+  };
+
+  let state = if window.is_maximized()? {
+    // Keep the last normal geometry so un-maximizing after a restart
+    // returns to it instead of to a monitor-sized window.
+    match load(app) {
+      Some(previous) => WindowState {
+        maximized: true,
+        ..previous
+      },
+      None => current(window, true)?,
+    }
+  } else {
+    current(window, false)?
+  };
+
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+  fs::write(path, serde_json::to_string_pretty(&state)?)?;
+  Ok(())
+}
+
+fn current<R: Runtime>(window: &Window<R>, maximized: bool) -> Result<WindowState> {
+  let position = window.outer_position()?;
+  let size = window.inner_size()?;
+  Ok(WindowState {
+    x: position.x,
+    y: position.y,
+    width: size.width,
+    height: size.height,
+    maximized,
+  })
+}
+
+pub fn save<R: Runtime>(window: &Window<R>) {
+  if let Err(e) = try_save(window) {
+    log::warn!("failed to save window state: {e}");
+  }
+}
+
+/// Saves the main window if it is still open. Quitting from the macOS app
+/// menu or with Cmd+Q exits without a `CloseRequested` event, so this runs
+/// on `RunEvent::Exit` as well.
+pub fn save_main<R: Runtime>(app: &AppHandle<R>) {
+  if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+    let webview: &Webview<R> = window.as_ref();
+    save(&webview.window());
+  }
+}
+
+/// A monitor's position and size.
+type Rect = (PhysicalPosition<i32>, PhysicalSize<u32>);
+
+/// Picks the monitor containing the saved window's centre, falling back to
+/// the first one, and clamps the geometry so the whole window is on it.
+fn fit(state: WindowState, monitors: &[Rect]) -> Option<Rect> {
+  let center_x = state.x.saturating_add((state.width / 2) as i32);
+  let center_y = state.y.saturating_add((state.height / 2) as i32);
+  let contains = |(pos, size): &&Rect| {
+    center_x >= pos.x
+      && center_y >= pos.y
+      && center_x < pos.x.saturating_add(size.width as i32)
+      && center_y < pos.y.saturating_add(size.height as i32)
+  };
+  let (pos, size) = monitors.iter().find(contains).or(monitors.first())?;
+
+  let width = state.width.clamp(MIN_WIDTH.min(size.width), size.width);
+  let height = state.height.clamp(MIN_HEIGHT.min(size.height), size.height);
+  let x = state.x.clamp(pos.x, pos.x + (size.width - width) as i32);
+  let y = state.y.clamp(pos.y, pos.y + (size.height - height) as i32);
+  Some((
+    PhysicalPosition::new(x, y),
+    PhysicalSize::new(width, height),
+  ))
+}
+
+fn try_restore<R: Runtime>(window: &WebviewWindow<R>) -> Result<()> {
+  let Some(state) = load(window.app_handle()) else {
+    return Ok(());
+  };
+  let monitors: Vec<Rect> = window
+    .available_monitors()?
+    .iter()
+    .map(|m| (*m.position(), *m.size()))
+    .collect();
+  if let Some((position, size)) = fit(state, &monitors) {
+    window.set_size(size)?;
+    window.set_position(position)?;
+  }
+  if state.maximized {
+    window.maximize()?;
+  }
+  Ok(())
+}
+
+/// Applies the saved geometry and then shows the window, which starts hidden
+/// so the default size never flashes on screen.
+pub fn restore<R: Runtime>(window: &WebviewWindow<R>) {
+  if let Err(e) = try_restore(window) {
+    log::warn!("failed to restore window state: {e}");
+  }
+  if let Err(e) = window.show() {
+    log::error!("failed to show main window: {e}");
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn monitor(x: i32, y: i32, width: u32, height: u32) -> Rect {
+    (
+      PhysicalPosition::new(x, y),
+      PhysicalSize::new(width, height),
+    )
+  }
+
+  fn state(x: i32, y: i32, width: u32, height: u32) -> WindowState {
+    WindowState {
+      x,
+      y,
+      width,
+      height,
+      maximized: false,
+    }
+  }
+
+  #[test]
+  fn moves_an_off_screen_window_to_the_first_monitor() {
+    let monitors = [monitor(0, 0, 1920, 1080), monitor(1920, 0, 2560, 1440)];
+
+    let fitted = fit(state(-5000, 3000, 1280, 720), &monitors);
+
+    assert_eq!(fitted, Some(monitor(0, 360, 1280, 720)));
+  }
+
+  #[test]
+  fn shrinks_a_window_larger_than_its_monitor() {
+    let monitors = [monitor(0, 0, 1920, 1080)];
+
+    let fitted = fit(state(100, 50, 2560, 1440), &monitors);
+
+    assert_eq!(fitted, Some(monitor(0, 0, 1920, 1080)));
+  }
+
+  #[test]
+  fn keeps_a_straddling_window_on_the_monitor_with_its_centre() {
+    let monitors = [monitor(0, 0, 1920, 1080), monitor(1920, 0, 2560, 1440)];
+
+    // Centre at x = 2000, just past the seam.
+    let fitted = fit(state(1400, 100, 1200, 800), &monitors);
+
+    assert_eq!(fitted, Some(monitor(1920, 100, 1200, 800)));
+  }
+
+  #[test]
+  fn gives_up_without_monitors() {
+    assert_eq!(fit(state(0, 0, 1280, 720), &[]), None);
+  }
+}